@@ -0,0 +1,321 @@
+use crate::assignment::Strategy;
+use crate::partitioner::{hmetis, kahypar, mtkahypar, native, patoh};
+use crate::preprocess;
+use crate::selection::Criterion;
+use clap::Parser;
+use hypergraph_formats::cnf::VariableHeuristic;
+use log::LevelFilter;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// CNF DIMACS input files, directories containing them or glob patterns.
+    /// Directories contribute all `.cnf` and `.dimacs` files directly inside them.
+    #[arg(required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    /// How many blocks to create
+    #[arg(short, long, env)]
+    pub blocks: Option<usize>,
+
+    /// Lower bound of how many blocks to create
+    #[arg(long, env)]
+    pub blocks_start: Option<usize>,
+
+    /// Upper bound of how many blocks to create
+    #[arg(long, env)]
+    pub blocks_end: Option<usize>,
+
+    /// Selects the amount of blocks automatically by growing it from the lower bound until the
+    /// criterion does not fall below the best value so far times the given factor.
+    /// The upper bound still limits the amounts tried, the lower bound defaulting to `2`.
+    #[arg(long, env, value_name = "FACTOR", requires = "blocks_end")]
+    pub auto_blocks: Option<f64>,
+
+    /// What to minimize when selecting the amount of blocks automatically
+    #[arg(
+        long,
+        env,
+        default_value_t = Criterion::Cut,
+        requires = "auto_blocks",
+        requires_if("estimated", "count_cubes")
+    )]
+    pub auto_blocks_criterion: Criterion,
+
+    /// Which epsilons (imbalance parameters) to use, separated by commas.
+    /// Defaults to `0.1` if neither this nor a range is specified.
+    #[arg(short, long, env, value_delimiter = ',')]
+    pub epsilon: Vec<f64>,
+
+    /// Lower bound of the epsilons (imbalance parameters) to use
+    #[arg(long, env)]
+    pub epsilon_start: Option<f64>,
+
+    /// Upper bound of the epsilons (imbalance parameters) to use
+    #[arg(long, env)]
+    pub epsilon_end: Option<f64>,
+
+    /// Step size between the epsilons (imbalance parameters) to use
+    #[arg(long, env)]
+    pub epsilon_step: Option<f64>,
+
+    /// Seed passed to all partitioners
+    #[arg(long, env, default_value_t = 0)]
+    pub seed: u64,
+
+    /// How many times to run each partitioner, each with a distinct seed starting at `seed`
+    #[arg(long, env, default_value_t = 1)]
+    pub repetitions: u64,
+
+    /// Path where to write statistics over the cut sizes of all seeds to.
+    #[arg(long, env)]
+    pub save_statistics: Option<PathBuf>,
+
+    /// Maximum allowed time in seconds for a d4 run
+    #[arg(short, long, env)]
+    pub timeout: Option<u64>,
+
+    /// Maximum allowed time in seconds for processing a single instance.
+    /// Once reached, the remaining runs of the instance are skipped.
+    #[arg(long, env)]
+    pub instance_timeout: Option<u64>,
+
+    /// Maximum allowed time in seconds for a partitioner run
+    #[arg(long, env)]
+    pub partitioner_timeout: Option<u64>,
+
+    /// Maximum allowed memory in MiB for each external process (d4 and partitioners)
+    #[arg(long, env)]
+    pub memory_limit: Option<u64>,
+
+    /// How to preprocess each input before partitioning it
+    #[arg(long, env, default_value_t = preprocess::Method::D4)]
+    pub preprocessing: preprocess::Method,
+
+    /// Native preprocessing techniques to switch off, separated by commas.
+    /// All of them are applied by default.
+    #[arg(long, env, value_delimiter = ',')]
+    pub preprocessing_disable: Vec<preprocess::Technique>,
+
+    /// Path where to write the preprocessed CNF to.
+    #[arg(long, env)]
+    pub save_cnf: Option<PathBuf>,
+
+    /// Path where to write partition outputs to.
+    /// Will get the name of the partitioner, the heuristic, the blocks, the epsilon and the seed
+    /// appended. `file.txt` becomes `file.txt.<partitioner>.<heuristic>.<blocks>.<epsilon>.<seed>`
+    #[arg(long, env)]
+    pub save_partitions: Option<PathBuf>,
+
+    /// Path where to write the conditioned and compacted parts of the split CNFs to.
+    /// Gets the same suffix as partition outputs plus the index of the part, written as
+    /// `<suffix>.<part>.cnf` along with the variable map `<suffix>.<part>.map`.
+    #[arg(long, env)]
+    pub save_split: Option<PathBuf>,
+
+    /// Path where to write the cut sets to along with the assignments conditioned on, one literal
+    /// per line followed by the name of its variable if given by the input.
+    /// Gets the same suffix as partition outputs followed by `.cut`.
+    #[arg(long, env)]
+    pub save_cut: Option<PathBuf>,
+
+    /// Path where to write the variable orders derived from the partitions to, cut variables first
+    /// followed by the others grouped by part, one variable per line.
+    /// Gets the same suffix as partition outputs followed by `.order`.
+    #[arg(long, env)]
+    pub save_order: Option<PathBuf>,
+
+    /// Path where to write the d-DNNFs compiled by d4 to, reporting their sizes.
+    /// The one of the original CNF is written to `<path>.nnf`, those of a run get the same suffix
+    /// as partition outputs followed by `.conditioned.nnf`, `.<part>.nnf` or `.composed.nnf`.
+    #[arg(long, env, visible_alias = "dump-file")]
+    pub dump_ddnnf: Option<PathBuf>,
+
+    /// Whether to validate the dumped d-DNNFs and check their model counts against the ones
+    /// reported by d4.
+    #[arg(long, env, requires = "dump_ddnnf")]
+    pub verify_ddnnf: bool,
+
+    /// How to choose the assignment of the cut set to condition on
+    #[arg(long, env, default_value_t = Strategy::First)]
+    pub assignment_strategy: Strategy,
+
+    /// The amount of random assignments drawn by the sample and max-count strategies.
+    #[arg(long, env, default_value_t = 5)]
+    pub assignment_samples: usize,
+
+    /// Counts the satisfying cut assignments to estimate the time of compiling the split CNFs for
+    /// each of them, enumerating them using at most the given amount of SAT calls.
+    /// Beyond that, their amount is estimated by random probes.
+    #[arg(long, env, value_name = "MAX_CALLS")]
+    pub count_cubes: Option<usize>,
+
+    /// The amount of random probes estimating the amount of satisfying cut assignments.
+    #[arg(long, env, default_value_t = 32, requires = "count_cubes")]
+    pub cube_probes: usize,
+
+    /// Composes a d-DNNF of the whole CNF from d-DNNFs of the parts compiled for each satisfiable
+    /// cut assignment.
+    /// Only done for cuts of at most the given size as the assignments grow exponentially.
+    #[arg(long, env, value_name = "MAX_CUT")]
+    pub compose: Option<usize>,
+
+    /// Whether to refine each partition by moving clauses between blocks as long as this reduces
+    /// the amount of variables shared between them.
+    #[arg(long, env)]
+    pub refine: bool,
+
+    /// The imbalance blocks may not exceed when receiving clauses during refinement.
+    /// Defaults to the epsilon of the partitioner.
+    #[arg(long, env, requires = "refine")]
+    pub refine_epsilon: Option<f64>,
+
+    /// Whether to additionally count each run by splitting its conditioned parts recursively using
+    /// the same partitioner and heuristic.
+    #[arg(long, env)]
+    pub recursive: bool,
+
+    /// The amount of clauses up to which parts are compiled instead of split further.
+    #[arg(long, env, default_value_t = 100)]
+    pub recursive_clauses: usize,
+
+    /// Timeout in seconds within which d4 is tried on a part before splitting it further.
+    #[arg(long, env)]
+    pub recursive_timeout: Option<u64>,
+
+    /// The maximum depth of the recursion, parts at this depth being compiled.
+    #[arg(long, env, default_value_t = 4)]
+    pub recursive_depth: usize,
+
+    /// Path where to write the recursion trees to as JSON.
+    /// Gets the same suffix as partition outputs followed by `.json`.
+    #[arg(long, env, requires = "recursive")]
+    pub save_trace: Option<PathBuf>,
+
+    /// Whether to additionally build a dtree of the clauses per run by recursively bisecting with
+    /// the same partitioner and heuristic.
+    #[arg(long, env)]
+    pub dtree: bool,
+
+    /// Path where to write the dtrees to in the c2d format.
+    /// Gets the same suffix as partition outputs followed by `.dtree`.
+    #[arg(long, env, requires = "dtree")]
+    pub save_dtree: Option<PathBuf>,
+
+    /// Variable heuristics to use for weighting the hypergraph.
+    /// Will create an additional run per partitioner and heuristic.
+    #[arg(long, env)]
+    pub heuristics: Vec<VariableHeuristic>,
+
+    /// Whether to partition only the largest connected component of each instance.
+    /// All components are counted separately either way.
+    #[arg(long, env)]
+    pub largest_component: bool,
+
+    /// Whether to skip running d4 on the original instance.
+    /// If specified, the output will contain 0s for values on the original instance.
+    #[arg(long, env)]
+    pub skip_original: bool,
+
+    /// Path to the d4 executable
+    #[arg(short, long, env)]
+    pub d4_path: PathBuf,
+
+    /// Which logging level to use
+    #[arg(short, long, env, default_value_t = LevelFilter::Info)]
+    pub logging: LevelFilter,
+
+    /// Whether to use KaHyPar
+    #[arg(long, env)]
+    pub kahypar_enable: bool,
+
+    /// Path to the KaHyPar executable
+    #[arg(long, env)]
+    pub kahypar_path: Option<PathBuf>,
+
+    /// Which KaHyPar metric to use
+    #[arg(long, env, default_value_t = kahypar::Metric::Cut)]
+    pub kahypar_metric: kahypar::Metric,
+
+    /// Which KaHyPar epsilon (imbalance parameter) to use, overriding the swept ones
+    #[arg(long, env)]
+    pub kahypar_epsilon: Option<f64>,
+
+    /// Whether to use Mt-KaHyPar
+    #[arg(long, env)]
+    pub mtkahypar_enable: bool,
+
+    /// Path to the Mt-KaHyPar executable
+    #[arg(long, env)]
+    pub mtkahypar_path: Option<PathBuf>,
+
+    /// Which Mt-KaHyPar metric to use
+    #[arg(long, env, default_value_t = mtkahypar::Metric::Cut)]
+    pub mtkahypar_metric: mtkahypar::Metric,
+
+    /// Which Mt-KaHyPar epsilon (imbalance parameter) to use, overriding the swept ones
+    #[arg(long, env)]
+    pub mtkahypar_epsilon: Option<f64>,
+
+    /// Whether to use PaToH
+    #[arg(long, env)]
+    pub patoh_enable: bool,
+
+    /// Path to the PaToH executable
+    #[arg(long, env)]
+    pub patoh_path: Option<PathBuf>,
+
+    /// Which PaToH metric to use
+    #[arg(long, env, default_value_t = patoh::Metric::Cut)]
+    pub patoh_metric: patoh::Metric,
+
+    /// Which PaToH preset to use
+    #[arg(long, env, default_value_t = patoh::Preset::Default)]
+    pub patoh_preset: patoh::Preset,
+
+    /// Whether to use hMETIS
+    #[arg(long, env)]
+    pub hmetis_enable: bool,
+
+    /// Path to the hMETIS executable of the chosen variant
+    #[arg(long, env)]
+    pub hmetis_path: Option<PathBuf>,
+
+    /// Which hMETIS executable is used
+    #[arg(long, env, default_value_t = hmetis::Variant::Khmetis)]
+    pub hmetis_variant: hmetis::Variant,
+
+    /// Which hMETIS UBfactor (imbalance parameter) to use.
+    /// Derived from epsilon if not specified.
+    #[arg(long, env)]
+    pub hmetis_ubfactor: Option<u32>,
+
+    /// How many runs hMETIS should do, keeping the best one (ignored by shmetis)
+    #[arg(long, env, default_value_t = 10)]
+    pub hmetis_runs: usize,
+
+    /// Which hMETIS coarsening scheme to use (ignored by shmetis)
+    #[arg(long, env, default_value_t = hmetis::CoarseningType::HybridFirstChoice)]
+    pub hmetis_ctype: hmetis::CoarseningType,
+
+    /// Which hMETIS refinement algorithm to use (only used by hmetis)
+    #[arg(long, env, default_value_t = hmetis::RefinementType::Fm)]
+    pub hmetis_rtype: hmetis::RefinementType,
+
+    /// Which hMETIS metric to use (only used by khmetis)
+    #[arg(long, env, default_value_t = hmetis::Metric::Cut)]
+    pub hmetis_metric: hmetis::Metric,
+
+    /// Native partitioning algorithms to run in-process
+    #[arg(long, env, value_delimiter = ',')]
+    pub native: Vec<native::Algorithm>,
+
+    /// Whether to enable the random partitioner, the same as `--native random`
+    #[arg(long, env)]
+    pub random_enable: bool,
+
+    /// Path to the Rust partitioner executable.
+    /// Deprecated as the random partitioner runs in-process, only accepted for compatibility.
+    #[arg(long, env)]
+    pub rust_path: Option<PathBuf>,
+}
@@ -0,0 +1,249 @@
+use crate::cnf::find_assignment;
+use crate::preprocess::{preprocess, Technique};
+use clap::ValueEnum;
+use hypergraph_formats::cnf::{Clause, Cnf, Literal};
+use num::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeSet;
+use strum::Display;
+
+/// How to choose the assignment of the cut set to condition on.
+#[derive(Debug, Display, Copy, Clone, Default, PartialEq, ValueEnum)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Strategy {
+    /// Takes the first model found by the SAT solver.
+    #[default]
+    First,
+    /// Takes a random model, choosing the value of each cut variable randomly where possible.
+    Random,
+    /// Takes several random models, compiling the split CNFs for each of them.
+    Sample,
+    /// Takes the random model with the highest estimated conditioned model count.
+    MaxCount,
+}
+
+/// Finds the assignments of the cut set to condition a CNF on, there being none for an
+/// unsatisfiable CNF.
+///
+/// All strategies but sampling result in a single assignment. Sampling draws the given amount of
+/// random assignments, keeping only distinct ones, while the maximum count strategy chooses among
+/// them and the first one.
+pub fn find_assignments(
+    cnf: &Cnf,
+    cut_set: &BTreeSet<u64>,
+    strategy: Strategy,
+    samples: usize,
+    seed: u64,
+) -> Vec<Vec<Literal>> {
+    if strategy == Strategy::First {
+        return find_assignment(cnf, cut_set).into_iter().collect();
+    }
+
+    let mut sampler = Sampler::new(cnf, seed);
+
+    let amount = match strategy {
+        Strategy::Random => 1,
+        _ => samples.max(1),
+    };
+
+    let mut assignments: Vec<Vec<Literal>> = Vec::with_capacity(amount);
+
+    for _ in 0..amount {
+        let Some(assignment) = sampler.sample(cut_set) else {
+            return Vec::new();
+        };
+
+        if !assignments.contains(&assignment) {
+            assignments.push(assignment);
+        }
+    }
+
+    if strategy != Strategy::MaxCount {
+        return assignments;
+    }
+
+    assignments.extend(find_assignment(cnf, cut_set));
+
+    assignments
+        .into_iter()
+        .map(|assignment| (estimate_count(cnf, &assignment), assignment))
+        // Prefer the earliest of equally estimated assignments.
+        .rev()
+        .max_by(|(first, _), (second, _)| first.cmp(second))
+        .map(|(_, assignment)| vec![assignment])
+        .unwrap_or_default()
+}
+
+/// Estimates the model count of a CNF conditioned on an assignment by an upper bound, being the
+/// amount of assignments of the variables left after propagating units.
+pub fn estimate_count(cnf: &Cnf, assignment: &[Literal]) -> BigInt {
+    let conditioned = cnf
+        .condition(assignment)
+        .expect("The assignment should only contain variables of the CNF.");
+
+    let (preprocessed, factor) = preprocess(&conditioned, &[Technique::Units, Technique::Free]);
+
+    // Only unit clauses remain in case of a contradiction or as a stand-in for no clauses.
+    let units: BTreeSet<Literal> = preprocessed
+        .clauses()
+        .iter()
+        .filter(|clause| clause.len() == 1)
+        .flat_map(Clause::literals)
+        .copied()
+        .collect();
+
+    if units.iter().any(|&literal| units.contains(&!literal)) {
+        return BigInt::ZERO;
+    }
+
+    let variables: BTreeSet<u64> = preprocessed
+        .clauses()
+        .iter()
+        .filter(|clause| clause.len() > 1)
+        .flat_map(Clause::variables)
+        .collect();
+
+    factor << variables.len()
+}
+
+/// Draws random satisfying assignments of a cut set using a SAT solver.
+struct Sampler {
+    solver: cadical::Solver,
+    rng: StdRng,
+}
+
+impl Sampler {
+    /// Creates a sampler holding the clauses of a CNF.
+    fn new(cnf: &Cnf, seed: u64) -> Self {
+        let mut solver: cadical::Solver = Default::default();
+
+        cnf.clauses().iter().for_each(|clause| {
+            solver.add_clause(clause.literals().map(|literal| literal.value() as i32))
+        });
+
+        Self {
+            solver,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Draws a satisfying assignment of the cut set, returning `None` for an unsatisfiable CNF.
+    ///
+    /// Each cut variable takes a random value unless that leaves the CNF unsatisfiable under the
+    /// values chosen before. The solver is only called in case the last model disagrees with the
+    /// random value.
+    fn sample(&mut self, cut_set: &BTreeSet<u64>) -> Option<Vec<Literal>> {
+        if self.solver.solve() != Some(true) {
+            return None;
+        }
+
+        let mut model = self.model(cut_set);
+        let mut assignment: Vec<Literal> = Vec::with_capacity(cut_set.len());
+
+        for (index, &variable) in cut_set.iter().enumerate() {
+            let positive = self.rng.random_bool(0.5);
+            assignment.push(Literal::from_variable(variable, positive));
+
+            if model[index] == positive {
+                continue;
+            }
+
+            let assumptions: Vec<i32> = assignment
+                .iter()
+                .map(|literal| literal.value() as i32)
+                .collect();
+
+            if self.solver.solve_with(assumptions) == Some(true) {
+                model = self.model(cut_set);
+            } else {
+                // The last model satisfies the other value.
+                let last = assignment.len() - 1;
+                assignment[last] = !assignment[last];
+            }
+        }
+
+        Some(assignment)
+    }
+
+    /// The values of the cut variables in the last model, undecided ones being positive.
+    fn model(&self, cut_set: &BTreeSet<u64>) -> Vec<bool> {
+        cut_set
+            .iter()
+            .map(|&variable| self.solver.value(variable as i32) != Some(false))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::assignment::{estimate_count, find_assignments, Strategy};
+    use crate::cnf::find_assignment;
+    use hypergraph_formats::cnf::{Cnf, Literal};
+    use num::BigInt;
+    use std::collections::BTreeSet;
+
+    /// `1` implies `2`, `3` and `4` while `-1` only implies `2`.
+    const CNF: &str = "p cnf 4 3\n-1 2 0\n-1 3 0\n-1 4 0\n";
+
+    fn cut() -> BTreeSet<u64> {
+        BTreeSet::from([1, 2])
+    }
+
+    #[test]
+    fn satisfying_assignments() {
+        let cnf: Cnf = CNF.parse().unwrap();
+
+        for strategy in [
+            Strategy::First,
+            Strategy::Random,
+            Strategy::Sample,
+            Strategy::MaxCount,
+        ] {
+            let assignments = find_assignments(&cnf, &cut(), strategy, 8, 0);
+
+            assert!(!assignments.is_empty());
+            assert!(strategy == Strategy::Sample || assignments.len() == 1);
+
+            for assignment in assignments {
+                assert_eq!(assignment.len(), 2);
+
+                let conditioned = cnf.condition(&assignment).unwrap();
+                assert!(find_assignment(&conditioned, &BTreeSet::new()).is_some());
+            }
+        }
+
+        // Sampling keeps distinct assignments only, of which there are three.
+        let samples = find_assignments(&cnf, &cut(), Strategy::Sample, 64, 0);
+        assert_eq!(samples.len(), 3);
+
+        // An unsatisfiable CNF has no assignments.
+        let unsatisfiable: Cnf = "p cnf 1 2\n1 0\n-1 0\n".parse().unwrap();
+        assert!(
+            find_assignments(&unsatisfiable, &BTreeSet::from([1]), Strategy::Sample, 4, 0)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn max_count() {
+        let cnf: Cnf = CNF.parse().unwrap();
+
+        // Assigning `-1` leaves `3` and `4` free, having the most models.
+        let assignment = &find_assignments(&cnf, &cut(), Strategy::MaxCount, 64, 0)[0];
+        assert!(!assignment[0].is_positive());
+
+        // Propagating `1` fixes all variables while `-1` leaves the others free.
+        let literals = |values: &[i64]| -> Vec<Literal> {
+            values
+                .iter()
+                .map(|&value| Literal::new(value).unwrap())
+                .collect()
+        };
+
+        assert_eq!(estimate_count(&cnf, &literals(&[1])), BigInt::from(1));
+        assert_eq!(estimate_count(&cnf, &literals(&[-1])), BigInt::from(8));
+        assert_eq!(estimate_count(&cnf, &literals(&[-1, 2])), BigInt::from(4));
+        assert_eq!(estimate_count(&cnf, &literals(&[1, -2])), BigInt::ZERO);
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use std::{fs, io};
+
+/// File extensions considered to be CNF instances when reading a directory.
+const EXTENSIONS: [&str; 2] = ["cnf", "dimacs"];
+
+/// Resolves the given inputs into a list of instance files.
+///
+/// Each input may either be a file, a directory or a glob pattern.
+/// Directories contribute all files with a CNF extension directly contained in them.
+/// The order of the inputs is kept while duplicates are removed.
+pub fn collect_inputs(inputs: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut seen = BTreeSet::new();
+    let mut instances = Vec::new();
+
+    for input in inputs {
+        let mut paths = if input.is_file() {
+            vec![input.clone()]
+        } else if input.is_dir() {
+            read_directory(input)?
+        } else {
+            expand_pattern(input)?
+        };
+
+        // Only add each instance once.
+        paths.retain(|path| seen.insert(path.clone()));
+        instances.append(&mut paths);
+    }
+
+    Ok(instances)
+}
+
+/// Collects all CNF files contained in a directory, sorted by their path.
+fn read_directory(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+
+        let is_cnf = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| EXTENSIONS.contains(&extension));
+
+        if path.is_file() && is_cnf {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Expands a glob pattern into all files matching it, sorted by their path.
+fn expand_pattern(pattern: &Path) -> io::Result<Vec<PathBuf>> {
+    let pattern = pattern.to_str().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("Input {} is not valid UTF-8.", pattern.display()),
+        )
+    })?;
+
+    let paths = glob::glob(pattern)
+        .map_err(|error| Error::new(ErrorKind::InvalidInput, error))?
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .map_err(Error::from)?;
+
+    let mut files: Vec<PathBuf> = paths.into_iter().filter(|path| path.is_file()).collect();
+
+    if files.is_empty() {
+        return Err(Error::new(
+            ErrorKind::NotFound,
+            format!("Input {pattern} is neither a file, a directory nor a matching pattern."),
+        ));
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// A point in time after which the processing of an instance should stop.
+#[derive(Debug, Copy, Clone)]
+pub struct Deadline(Option<Instant>);
+
+impl Deadline {
+    /// Creates a deadline the given amount of seconds from now, if any.
+    pub fn new(timeout: Option<u64>) -> Self {
+        Self(timeout.map(|timeout| Instant::now() + Duration::from_secs(timeout)))
+    }
+
+    /// Whether the deadline has passed.
+    pub fn expired(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Limits the given timeout in seconds to the time left until the deadline.
+    ///
+    /// Returns an error in case the deadline has already passed.
+    pub fn limit(&self, timeout: Option<u64>) -> io::Result<Option<u64>> {
+        let Some(deadline) = self.0 else {
+            return Ok(timeout);
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        if remaining.is_zero() {
+            return Err(Error::new(ErrorKind::TimedOut, "Instance timeout reached."));
+        }
+
+        // Round up to not cut off the last partial second.
+        let remaining = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+
+        Ok(Some(
+            timeout.map_or(remaining, |timeout| timeout.min(remaining)),
+        ))
+    }
+
+    /// Returns an error in case the deadline has passed.
+    pub fn check(&self) -> io::Result<()> {
+        if self.expired() {
+            return Err(Error::new(ErrorKind::TimedOut, "Instance timeout reached."));
+        }
+
+        Ok(())
+    }
+}
+
+/// How processing an instance ended.
+#[derive(Debug)]
+pub enum Status {
+    /// All runs finished.
+    Finished,
+    /// The per-instance timeout was reached.
+    Timeout,
+    /// Processing the instance failed.
+    Failed(Error),
+}
+
+impl From<io::Result<()>> for Status {
+    fn from(value: io::Result<()>) -> Self {
+        match value {
+            Ok(()) => Status::Finished,
+            Err(error) if error.kind() == ErrorKind::TimedOut => Status::Timeout,
+            Err(error) => Status::Failed(error),
+        }
+    }
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Finished => write!(f, "finished"),
+            Status::Timeout => write!(f, "timeout"),
+            Status::Failed(error) => write!(f, "failed ({error})"),
+        }
+    }
+}
+
+/// The result of processing a single instance of a batch.
+#[derive(Debug)]
+struct InstanceSummary {
+    instance: PathBuf,
+    status: Status,
+    runs: usize,
+    time: u128,
+}
+
+/// Summary over all instances processed in a batch.
+#[derive(Debug, Default)]
+pub struct Summary(Vec<InstanceSummary>);
+
+impl Summary {
+    /// Creates an empty summary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the result of processing an instance.
+    pub fn add(&mut self, instance: PathBuf, status: Status, runs: usize, time: u128) {
+        self.0.push(InstanceSummary {
+            instance,
+            status,
+            runs,
+            time,
+        });
+    }
+
+    /// The amount of instances that did not finish all of their runs.
+    pub fn unfinished(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|summary| !matches!(summary.status, Status::Finished))
+            .count()
+    }
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Summary of {} instance(s):", self.0.len())?;
+
+        self.0.iter().try_for_each(|summary| {
+            writeln!(
+                f,
+                "  {}: {}, {} run(s), {} ms",
+                summary.instance.display(),
+                summary.status,
+                summary.runs,
+                summary.time
+            )
+        })?;
+
+        write!(
+            f,
+            "{} finished, {} unfinished",
+            self.0.len() - self.unfinished(),
+            self.unfinished()
+        )
+    }
+}
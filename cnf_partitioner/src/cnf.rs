@@ -1,185 +1,121 @@
-use dimacs::{Clause, Instance, Lit, Sign, Var};
-use hypergraph::Partition;
-use std::collections::{BTreeSet, HashMap};
+use hypergraph_formats::cnf::{Cnf, Literal};
+use std::collections::BTreeSet;
 
-pub fn split_cnf(partition: &Partition, original: &Instance) -> Vec<Instance> {
-    let (clauses, &num_vars) = match original {
-        Instance::Cnf { clauses, num_vars } => (clauses, num_vars),
-        Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-    };
-
-    // Helper instance to collect all clauses for their respective CNF.
-    let mut instances: HashMap<usize, Vec<Clause>> = HashMap::new();
-
-    // Iterate over all clause -> block pairs.
-    partition
-        .iter()
-        .enumerate()
-        // Get the clauses content.
-        .map(|(clause_index, block)| (clauses[clause_index].clone(), block))
-        // Add the clause to the correct block (CNF).
-        .for_each(|(clause, block)| match instances.get_mut(block) {
-            Some(instance) => instance.push(clause),
-            None => {
-                // Create a new set of clauses in case there is none.
-                instances.insert(*block, vec![clause]);
-            }
-        });
-
-    instances
-        .into_values()
-        .map(|clauses| Instance::cnf(num_vars, clauses))
-        .collect()
-}
-
-/// Calculates the cut between multiple CNF instances.
+/// Searches for an assignment of the given cut set satisfying the CNF.
 ///
-/// The cut consists of those variables that are shared between instances.
-pub fn get_cut_variables(instances: &[Instance]) -> BTreeSet<u64> {
-    let mut cut = BTreeSet::new();
-
-    // Collect the variables beforehand.
-    let instance_variables: Vec<BTreeSet<u64>> =
-        instances.iter().map(get_instance_variables).collect();
-
-    // For each instance ...
-    instance_variables
-        .iter()
-        .enumerate()
-        .for_each(|(index, a)| {
-            // ... compare it with each other instance not considered yet ...
-            instance_variables.iter().skip(index + 1).for_each(|b| {
-                // ... and add the cut of these.
-                cut.extend(a.intersection(b));
-            });
-        });
-
-    cut
-}
-
-/// Searches for an assignment of the given cut set satisfying the CNF instance.
-pub fn find_assignment(instance: &Instance, cut_set: &BTreeSet<u64>) -> Vec<i32> {
+/// Returns `None` in case the CNF is unsatisfiable.
+pub fn find_assignment(cnf: &Cnf, cut_set: &BTreeSet<u64>) -> Option<Vec<Literal>> {
     // Initialize the SAT solver.
     let mut solver: cadical::Solver = Default::default();
 
     // Transform the clauses into the required format for the solver.
-    match instance {
-        Instance::Cnf { clauses, .. } => {
-            clauses.iter().for_each(|clause| {
-                solver.add_clause(clause.lits().iter().map(|literal| {
-                    let variable = literal.var().to_u64() as i32;
-                    match literal.sign() {
-                        Sign::Pos => variable,
-                        Sign::Neg => -variable,
-                    }
-                }))
-            });
-        }
-        Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-    }
+    cnf.clauses().iter().for_each(|clause| {
+        solver.add_clause(clause.literals().map(|literal| literal.value() as i32))
+    });
 
     // Solve the formula.
-    assert!(solver.solve().expect("Failed to solve CNF."));
+    if !solver.solve().expect("Failed to solve CNF.") {
+        return None;
+    }
 
     // For each variable in the cut set ...
-    cut_set
+    let assignment = cut_set
         .iter()
-        .map(|&variable| variable as i32)
         // ... find the value in the solved instance.
-        .map(|variable| match solver.value(variable) {
+        .map(|&variable| match solver.value(variable as i32) {
             // Return it as a positive literal in case it was not decided or done so positively ...
-            None | Some(true) => variable,
+            None | Some(true) => Literal::from_variable(variable, true),
             // ... and negative otherwise.
-            Some(false) => -variable,
+            Some(false) => Literal::from_variable(variable, false),
         })
-        .collect()
-}
+        .collect();
 
-/// Conditions a CNF instance on the provided assignment by adding each literal as a unit clause.
-/// Additionally adds each variable that is neither part of the assignment nor occurring in the formula.
-pub fn condition_instance(instance: &Instance, assignment: &[i32]) -> Instance {
-    let (mut clauses, &num_vars) = match instance {
-        Instance::Cnf { clauses, num_vars } => (clauses.to_vec(), num_vars),
-        Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-    };
-
-    // Add each assigned literal to the formula as a unit clause.
-    clauses.extend(
-        assignment
-            .iter()
-            .map(|&literal| Clause::from_vec(vec![Lit::from_i64(literal as i64)])),
-    );
-
-    // Generate a CNF instance to be further extended.
-    let instance = Instance::cnf(num_vars, clauses.clone());
-
-    // Get all variables in the CNF, including the ones just added.
-    let variables = get_instance_variables(&instance);
-
-    // Add each variable that is not part of the assignment and the formula.
-    clauses.extend(
-        (1..=num_vars)
-            .filter(|variable| !variables.contains(variable))
-            .map(|variable| Clause::from_vec(vec![Lit::from_i64(variable as i64)])),
-    );
-
-    Instance::cnf(num_vars, clauses)
+    Some(assignment)
 }
 
-/// Creates the set of variables in a CNF instance.
-fn get_instance_variables(instance: &Instance) -> BTreeSet<u64> {
-    match instance {
-        Instance::Cnf { clauses, .. } => clauses
-            .iter()
-            .flat_map(Clause::lits)
-            .copied()
-            .map(Lit::var)
-            .map(Var::to_u64)
-            .collect(),
-
-        Instance::Sat { .. } => panic!("Expected SAT but found CNF DIMACS."),
-    }
-}
+/// Counts the variables of the original CNF occurring in none of the given parts.
+///
+/// These are free when counting the models of the parts.
+pub fn count_free_variables(original: &Cnf, parts: &[Cnf]) -> usize {
+    let variables: BTreeSet<u64> = parts.iter().flat_map(Cnf::variables).collect();
 
-/// Serializes a CNF into the DIMACS format.
-pub fn serialize_cnf(cnf: &Instance) -> String {
-    let (clauses, &num_vars) = match cnf {
-        Instance::Cnf { clauses, num_vars } => (clauses, num_vars),
-        Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-    };
+    (1..=original.num_vars())
+        .filter(|variable| !variables.contains(variable))
+        .count()
+}
 
-    let mut buffer = String::new();
+/// Serializes a variable map as created by [`Cnf::compact`].
+///
+/// Each line contains a variable of the compacted CNF followed by the original one.
+pub fn serialize_variable_map(variables: &[u64]) -> String {
+    variables
+        .iter()
+        .enumerate()
+        .map(|(index, variable)| format!("{} {}\n", index + 1, variable))
+        .collect()
+}
 
-    // Start with the header.
-    let num_clauses = clauses.len();
-    buffer.push_str(format!("p cnf {num_vars} {num_clauses}\n").as_str());
+/// Orders the variables of a CNF by the parts its clauses were split into.
+///
+/// The cut variables come first, those occurring in more parts preceding the others. The remaining
+/// variables follow grouped by the part they occur in, and those occurring in no part come last.
+/// Ties are broken by the variables themselves.
+pub fn variable_order(original: &Cnf, parts: &[Cnf]) -> Vec<u64> {
+    let variables: Vec<BTreeSet<u64>> = parts.iter().map(Cnf::variables).collect();
 
-    // For each clause and its literals ...
-    clauses.iter().map(Clause::lits).for_each(|literals| {
-        // ... add them to the output buffer.
-        literals.iter().for_each(|literal| {
-            buffer.push_str(format!("{} ", serialize_literal(literal)).as_str())
-        });
+    // The amount of parts each variable occurs in.
+    let mut occurrences = vec![0usize; original.num_vars() as usize + 1];
 
-        // Each clause end with a `0`.
-        buffer.push_str("0\n");
-    });
+    variables
+        .iter()
+        .flatten()
+        .for_each(|&variable| occurrences[variable as usize] += 1);
 
-    buffer
-}
+    let mut order: Vec<u64> = (1..=original.num_vars())
+        .filter(|&variable| occurrences[variable as usize] > 1)
+        .collect();
 
-/// Serializes a CNF literal.
-pub fn serialize_literal(literal: &Lit) -> String {
-    let mut buffer = String::new();
+    order.sort_by_key(|&variable| std::cmp::Reverse(occurrences[variable as usize]));
 
-    // Add the sign if necessary.
-    match literal.sign() {
-        Sign::Pos => {}
-        Sign::Neg => buffer.push('-'),
+    for part in &variables {
+        order.extend(
+            part.iter()
+                .filter(|&&variable| occurrences[variable as usize] == 1),
+        );
     }
 
-    // Add the actual value.
-    buffer.push_str(literal.var().to_u64().to_string().as_str());
+    order.extend((1..=original.num_vars()).filter(|&variable| occurrences[variable as usize] == 0));
+    order
+}
+
+/// Serializes a variable order as created by [`variable_order`], one variable per line.
+pub fn serialize_variable_order(order: &[u64]) -> String {
+    order
+        .iter()
+        .map(|variable| format!("{variable}\n"))
+        .collect()
+}
 
-    buffer
+/// Serializes a cut set along with its assignment if any, one variable per line.
+///
+/// Each line contains the literal of the variable as assigned, or the variable itself without an
+/// assignment, followed by its name if it has one.
+pub fn serialize_cut(cnf: &Cnf, cut_set: &BTreeSet<u64>, assignment: Option<&[Literal]>) -> String {
+    cut_set
+        .iter()
+        .map(|&variable| {
+            let literal = assignment
+                .and_then(|assignment| {
+                    assignment
+                        .iter()
+                        .find(|literal| literal.variable() == variable)
+                })
+                .map_or(variable as i64, Literal::value);
+
+            match cnf.name(variable) {
+                Some(name) => format!("{literal} {name}\n"),
+                None => format!("{literal}\n"),
+            }
+        })
+        .collect()
 }
@@ -0,0 +1,51 @@
+use hypergraph::Hypergraph;
+use hypergraph_formats::cnf::Cnf;
+use hypergraph_formats::Graph;
+use num::BigInt;
+
+/// The decomposition of a CNF into independent components.
+#[derive(Debug, Clone, Default)]
+pub struct Decomposition {
+    /// The amount of clauses in each component.
+    pub sizes: Vec<usize>,
+    /// The product of the model counts of all components, doubled for each free variable.
+    pub count: BigInt,
+    /// The wall-clock time taken for counting all components in ms.
+    pub time: u128,
+}
+
+/// Splits a CNF into its connected components of clauses sharing variables.
+///
+/// Each component keeps all variables of the CNF, their names and the relative order of its
+/// clauses.
+/// Components are ordered by their first clause.
+pub fn components(cnf: &Cnf) -> Vec<Cnf> {
+    // Clauses are the vertices of the dual hypergraph.
+    let graph = Hypergraph::from(&Graph::from(cnf).dual());
+
+    graph
+        .components()
+        .into_iter()
+        .map(|clauses| {
+            Cnf::new(
+                cnf.num_vars(),
+                clauses
+                    .into_iter()
+                    .map(|clause| cnf.clauses()[clause].clone())
+                    .collect(),
+            )
+            .expect("Components should only contain variables of the CNF.")
+            .with_names(cnf.names().clone())
+        })
+        .collect()
+}
+
+/// Finds the index of the component with the most clauses, preferring the first one.
+pub fn largest(components: &[Cnf]) -> Option<usize> {
+    components
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, component)| component.len())
+        .map(|(index, _)| index)
+}
@@ -0,0 +1,171 @@
+use crate::batch::Deadline;
+use crate::d4::D4;
+use crate::pipeline::compile;
+use ddnnf::{Composer, Nnf};
+use hypergraph_formats::cnf::{Cnf, Literal};
+use log::info;
+use num::BigInt;
+use std::collections::BTreeSet;
+use std::io::{self, Error, ErrorKind};
+use tempfile::NamedTempFile;
+
+/// A d-DNNF of a whole CNF composed of the d-DNNFs of its parts, compiled per cut assignment.
+#[derive(Debug, Clone, Default)]
+pub struct Composition {
+    /// The amount of satisfiable cut assignments the parts were compiled for.
+    pub assignments: usize,
+    /// The model count of the composed d-DNNF, `0` in case of a timeout.
+    pub count: BigInt,
+    /// The wall-clock time taken for compiling all parts in ms, `u128::MAX` in case of a timeout.
+    pub time: u128,
+}
+
+impl Composition {
+    /// Whether compiling any of the parts reached the time limit.
+    pub fn timed_out(&self) -> bool {
+        self.time == u128::MAX
+    }
+}
+
+/// The state while composing a d-DNNF.
+struct Composing<'a> {
+    d4: &'a D4,
+    parts: &'a [Cnf],
+    timeout: Option<u64>,
+    deadline: Deadline,
+    /// The solver holding the whole CNF for pruning unsatisfiable assignments.
+    solver: cadical::Solver,
+    composer: Composer,
+    composition: Composition,
+}
+
+/// Composes a d-DNNF of a CNF from the d-DNNFs of the parts it was split into.
+///
+/// The cut variables are decided on in ascending order, skipping assignments the CNF is
+/// unsatisfiable under. For each satisfiable assignment of all cut variables, the conditioned
+/// parts share no variables, so their d-DNNFs are compiled and conjoined.
+///
+/// Returns `None` as the d-DNNF in case compiling a part reached the time limit.
+pub fn compose(
+    d4: &D4,
+    cnf: &Cnf,
+    parts: &[Cnf],
+    cut: &BTreeSet<u64>,
+    timeout: Option<u64>,
+    deadline: Deadline,
+) -> io::Result<(Option<Nnf>, Composition)> {
+    let mut solver: cadical::Solver = Default::default();
+
+    cnf.clauses().iter().for_each(|clause| {
+        solver.add_clause(clause.literals().map(|literal| literal.value() as i32))
+    });
+
+    let mut composing = Composing {
+        d4,
+        parts,
+        timeout,
+        deadline,
+        solver,
+        composer: Composer::new(),
+        composition: Composition::default(),
+    };
+
+    let cut: Vec<u64> = cut.iter().copied().collect();
+
+    let Some(root) = composing.branch(&mut Vec::new(), &cut)? else {
+        composing.composition.count = BigInt::ZERO;
+        composing.composition.time = u128::MAX;
+        return Ok((None, composing.composition));
+    };
+
+    let nnf = composing.composer.finish(root);
+    composing.composition.count = nnf.count(cnf.num_vars());
+
+    info!(
+        "Composed a d-DNNF from {} cut assignment(s), model count: {}",
+        composing.composition.assignments, composing.composition.count
+    );
+
+    Ok((Some(nnf), composing.composition))
+}
+
+impl Composing<'_> {
+    /// Decides on the remaining cut variables under a partial assignment, returning the node
+    /// representing it unless a part timed out.
+    fn branch(&mut self, assignment: &mut Vec<Literal>, cut: &[u64]) -> io::Result<Option<usize>> {
+        let assumptions: Vec<i32> = assignment
+            .iter()
+            .map(|literal| literal.value() as i32)
+            .collect();
+
+        if self.solver.solve_with(assumptions) != Some(true) {
+            return Ok(Some(self.composer.constant(false)));
+        }
+
+        let Some((&variable, cut)) = cut.split_first() else {
+            return self.conjoin(assignment);
+        };
+
+        let mut children = Vec::with_capacity(2);
+
+        for positive in [true, false] {
+            assignment.push(Literal::from_variable(variable, positive));
+            let child = self.branch(assignment, cut)?;
+            assignment.pop();
+
+            let Some(child) = child else {
+                return Ok(None);
+            };
+
+            children.push(child);
+        }
+
+        Ok(Some(self.composer.decide(
+            variable,
+            children[0],
+            children[1],
+        )))
+    }
+
+    /// Compiles the parts conditioned on an assignment of all cut variables and conjoins them.
+    fn conjoin(&mut self, assignment: &[Literal]) -> io::Result<Option<usize>> {
+        self.composition.assignments += 1;
+
+        let literals: Vec<i64> = assignment.iter().map(Literal::value).collect();
+        let mut roots = Vec::with_capacity(self.parts.len());
+
+        for part in self.parts {
+            // Only the variables of the part are relevant for its d-DNNF.
+            let conditioned = part
+                .condition(assignment)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+            let (conditioned, variables) = conditioned.compact();
+
+            let dump = NamedTempFile::new()?.into_temp_path();
+            let compilation = compile(
+                self.d4,
+                &conditioned,
+                Some(&dump),
+                self.timeout,
+                self.deadline,
+                false,
+            )?;
+
+            if compilation.timed_out() {
+                return Ok(None);
+            }
+
+            self.composition.time = self.composition.time.saturating_add(compilation.time);
+
+            // The cut variables are decided on already, leaving the parts disjoint.
+            let nnf = Nnf::read(&dump)
+                .map_err(|error| Error::new(ErrorKind::InvalidData, error))?
+                .rename(&variables)
+                .condition(&literals);
+
+            roots.push(self.composer.add(&nnf));
+        }
+
+        Ok(Some(self.composer.conjoin(&roots)))
+    }
+}
@@ -0,0 +1,170 @@
+use hypergraph_formats::cnf::Cnf;
+use num::BigInt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeSet;
+
+/// The satisfying assignments of a cut set, each being a cube to compile the parts for.
+#[derive(Debug, Clone, Default)]
+pub struct Cubes {
+    /// The amount of satisfying assignments, estimated unless exact.
+    pub count: BigInt,
+    /// Whether the amount was counted by enumerating all assignments.
+    pub exact: bool,
+}
+
+/// Counts the assignments of a cut set the CNF is satisfiable under.
+///
+/// The assignments are enumerated by deciding on the cut variables in ascending order, pruning
+/// unsatisfiable ones. In case this takes more than the given amount of SAT calls, the amount is
+/// estimated instead by averaging random probes down the same tree, each multiplying the amount of
+/// satisfiable choices along its path.
+pub fn count_cubes(
+    cnf: &Cnf,
+    cut_set: &BTreeSet<u64>,
+    limit: usize,
+    probes: usize,
+    seed: u64,
+) -> Cubes {
+    let mut solver: cadical::Solver = Default::default();
+
+    cnf.clauses().iter().for_each(|clause| {
+        solver.add_clause(clause.literals().map(|literal| literal.value() as i32))
+    });
+
+    if solver.solve() != Some(true) {
+        return Cubes {
+            count: BigInt::ZERO,
+            exact: true,
+        };
+    }
+
+    let mut counting = Counting {
+        solver,
+        calls: 0,
+        limit,
+    };
+
+    let cut: Vec<i32> = cut_set.iter().map(|&variable| variable as i32).collect();
+
+    if let Some(count) = counting.enumerate(&mut Vec::new(), &cut) {
+        return Cubes { count, exact: true };
+    }
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let probes = probes.max(1);
+
+    let sum: BigInt = (0..probes).map(|_| counting.probe(&cut, &mut rng)).sum();
+
+    Cubes {
+        count: sum / probes,
+        exact: false,
+    }
+}
+
+/// The state while counting satisfying assignments.
+struct Counting {
+    solver: cadical::Solver,
+    /// The amount of SAT calls made while enumerating.
+    calls: usize,
+    /// The amount of SAT calls after which enumerating is given up.
+    limit: usize,
+}
+
+impl Counting {
+    /// Counts the satisfying extensions of a satisfiable assignment to the remaining cut
+    /// variables, returning `None` in case the limit is reached.
+    fn enumerate(&mut self, assignment: &mut Vec<i32>, cut: &[i32]) -> Option<BigInt> {
+        let Some((&variable, cut)) = cut.split_first() else {
+            return Some(BigInt::from(1));
+        };
+
+        let mut count = BigInt::ZERO;
+
+        for literal in [variable, -variable] {
+            if self.calls >= self.limit {
+                return None;
+            }
+
+            self.calls += 1;
+            assignment.push(literal);
+
+            if self.solver.solve_with(assignment.iter().copied()) == Some(true) {
+                count += self.enumerate(assignment, cut)?;
+            }
+
+            assignment.pop();
+        }
+
+        Some(count)
+    }
+
+    /// Follows a random path of satisfiable choices, returning the product of the amounts of
+    /// satisfiable choices along it.
+    fn probe(&mut self, cut: &[i32], rng: &mut StdRng) -> BigInt {
+        let mut assignment = Vec::with_capacity(cut.len());
+        let mut estimate = BigInt::from(1);
+
+        for &variable in cut {
+            let choices: Vec<i32> = [variable, -variable]
+                .into_iter()
+                .filter(|&literal| {
+                    let assumptions = assignment.iter().copied().chain([literal]);
+                    self.solver.solve_with(assumptions) == Some(true)
+                })
+                .collect();
+
+            // The assignment so far is satisfiable, so one of the choices is as well.
+            estimate *= choices.len();
+            assignment.push(choices[rng.random_range(0..choices.len())]);
+        }
+
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cubes::count_cubes;
+    use hypergraph_formats::cnf::Cnf;
+    use num::BigInt;
+    use std::collections::BTreeSet;
+
+    /// `1` implies `2`, `3` and `4` while `-1` only implies `2`.
+    const CNF: &str = "p cnf 4 3\n-1 2 0\n-1 3 0\n-1 4 0\n";
+
+    #[test]
+    fn exact() {
+        let cnf: Cnf = CNF.parse().unwrap();
+
+        // All assignments of `1` and `2` but `1 -2` are satisfiable.
+        let cubes = count_cubes(&cnf, &BTreeSet::from([1, 2]), 16, 4, 0);
+        assert_eq!(cubes.count, BigInt::from(3));
+        assert!(cubes.exact);
+
+        let cubes = count_cubes(&cnf, &BTreeSet::from([2, 3, 4]), 16, 4, 0);
+        assert_eq!(cubes.count, BigInt::from(8));
+        assert!(cubes.exact);
+
+        // An unsatisfiable CNF has no satisfying assignments.
+        let unsatisfiable: Cnf = "p cnf 1 2\n1 0\n-1 0\n".parse().unwrap();
+        let cubes = count_cubes(&unsatisfiable, &BTreeSet::from([1]), 16, 4, 0);
+        assert_eq!(cubes.count, BigInt::ZERO);
+        assert!(cubes.exact);
+    }
+
+    #[test]
+    fn estimated() {
+        let cnf: Cnf = CNF.parse().unwrap();
+
+        // Each probe estimates either `2` or `4` assignments.
+        let cubes = count_cubes(&cnf, &BTreeSet::from([1, 2]), 1, 16, 0);
+        assert!(!cubes.exact);
+        assert!(BigInt::from(2) <= cubes.count && cubes.count <= BigInt::from(4));
+
+        // Probes are exact for independent variables.
+        let cubes = count_cubes(&cnf, &BTreeSet::from([2, 3, 4]), 1, 4, 0);
+        assert!(!cubes.exact);
+        assert_eq!(cubes.count, BigInt::from(8));
+    }
+}
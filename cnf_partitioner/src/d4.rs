@@ -1,27 +1,155 @@
-use log::{debug, info, trace, warn};
+use crate::process::{self, Limits, ProcessOutput, Usage};
+use ddnnf::{Metrics, Nnf, NnfError};
+use log::{debug, info, warn};
 use num::BigInt;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::os::unix::process::ExitStatusExt;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
-use std::str::{from_utf8, FromStr};
-use std::sync::mpsc::{channel, TryRecvError};
-use std::thread;
-use std::thread::{sleep, yield_now};
-use std::time::{Duration, Instant};
+use std::process::Command;
+
+/// How many of the last lines of stderr to keep in errors.
+const STDERR_LINES: usize = 10;
 
 /// Wrapper around `d4` for preprocessing and compiling a CNF.
-pub struct D4(PathBuf);
+pub struct D4 {
+    path: PathBuf,
+    limits: Limits,
+}
+
+/// The result of compiling a CNF.
+#[derive(Debug, Clone, Default)]
+pub struct Compilation {
+    /// The wall-clock time taken in ms, `u128::MAX` in case of a timeout.
+    pub time: u128,
+    /// The model count, `0` in case of a timeout or an unsatisfiable CNF.
+    pub count: BigInt,
+    /// The resources used by d4.
+    pub usage: Usage,
+    /// The size of the compiled d-DNNF, all `0` if it was not dumped.
+    pub metrics: Metrics,
+}
+
+impl Compilation {
+    /// Whether d4 was stopped by reaching the time limit.
+    pub fn timed_out(&self) -> bool {
+        self.time == u128::MAX
+    }
+}
+
+/// Reasons for a d4 run to fail.
+#[derive(Debug)]
+pub enum D4Error {
+    /// The process could not be run.
+    Process(io::Error),
+    /// The time limit was reached while preprocessing.
+    Timeout,
+    /// The process was killed by a signal, e.g. by exceeding the memory limit.
+    Signal { signal: i32, stderr: String },
+    /// The process exited unsuccessfully without reporting a model count.
+    Exit { code: i32, stderr: String },
+    /// The output did not contain a model count.
+    Output { stderr: String },
+    /// The dumped d-DNNF could not be read.
+    Dump(NnfError),
+}
+
+impl D4Error {
+    /// Creates the error for an unsuccessful process, preferring the signal over the exit code.
+    fn from_output(output: &ProcessOutput) -> Self {
+        let stderr = tail(&output.stderr);
+
+        if let Some(signal) = output.status.signal() {
+            D4Error::Signal { signal, stderr }
+        } else if let Some(code) = output.status.code().filter(|&code| code != 0) {
+            D4Error::Exit { code, stderr }
+        } else {
+            D4Error::Output { stderr }
+        }
+    }
+}
+
+impl Display for D4Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            D4Error::Process(error) => write!(f, "failed to run d4: {error}"),
+            D4Error::Timeout => write!(f, "d4 reached the time limit"),
+            D4Error::Signal { signal, stderr } => {
+                write!(f, "d4 was killed by signal {signal}, stderr: {stderr:?}")
+            }
+            D4Error::Exit { code, stderr } => {
+                write!(f, "d4 exited with code {code}, stderr: {stderr:?}")
+            }
+            D4Error::Output { stderr } => {
+                write!(f, "d4 reported no model count, stderr: {stderr:?}")
+            }
+            D4Error::Dump(error) => write!(f, "failed to read the d-DNNF dumped by d4: {error}"),
+        }
+    }
+}
+
+impl Error for D4Error {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            D4Error::Process(error) => Some(error),
+            D4Error::Dump(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<D4Error> for io::Error {
+    fn from(value: D4Error) -> Self {
+        match value {
+            D4Error::Timeout => io::Error::new(io::ErrorKind::TimedOut, value),
+            value => io::Error::other(value),
+        }
+    }
+}
+
+/// Finds the model count in the output of d4, being `0` for an unsatisfiable CNF.
+///
+/// The last solution line, starting with `s`, is used while all other lines are ignored.
+pub fn parse_count(stdout: &str) -> Option<BigInt> {
+    let value = stdout
+        .lines()
+        .rev()
+        .map(str::split_whitespace)
+        .find_map(|mut tokens| (tokens.next() == Some("s")).then(|| tokens.last()))??;
+
+    match value {
+        "UNSATISFIABLE" => Some(BigInt::ZERO),
+        count => count.parse().ok(),
+    }
+}
+
+/// Keeps the last lines of a process output.
+pub(crate) fn tail(output: &[u8]) -> String {
+    let output = String::from_utf8_lossy(output);
+    let lines: Vec<&str> = output.trim_end().lines().collect();
+
+    lines[lines.len().saturating_sub(STDERR_LINES)..].join("\n")
+}
 
 impl D4 {
     /// Creates a new d4 instance using the binary at the given path.
-    pub fn new(path: PathBuf) -> Self {
-        Self(path)
+    ///
+    /// The memory limit in the given limits applies to all d4 runs.
+    pub fn new(path: PathBuf, limits: Limits) -> Self {
+        Self { path, limits }
     }
 
     /// Processes a CNF with `d4` while writing the preprocessed input to the specified file
-    /// and returning the time taken and the model count.
-    pub fn preprocess(&self, cnf: PathBuf, out_file: &Path) -> Result<u128, ()> {
+    /// and returning the time taken.
+    pub fn preprocess(
+        &self,
+        cnf: PathBuf,
+        out_file: &Path,
+        timeout: Option<u64>,
+    ) -> Result<u128, D4Error> {
         // Create the d4 process and run it.
-        let mut run = Command::new(&self.0);
+        let mut run = Command::new(&self.path);
 
         run.args([
             "--input",
@@ -36,15 +164,31 @@ impl D4 {
                 .expect("Failed to serialize preprocessed output file."),
         ]);
 
-        trace!("{:?}", run);
+        let output =
+            process::run(&mut run, self.limits.with_time(timeout)).map_err(D4Error::Process)?;
+
+        if output.timed_out {
+            warn!("Timeout reached while preprocessing.");
+            return Err(D4Error::Timeout);
+        }
 
-        let start = Instant::now();
-        run.output().expect("Failed to run d4 preprocessing.");
-        Ok(start.elapsed().as_millis())
+        if !output.status.success() {
+            return Err(D4Error::from_output(&output));
+        }
+
+        Ok(output.time)
     }
 
-    /// Compiles a CNF into a d-DNNF, returning the time taken and the model count.
-    pub fn compile(&self, cnf: PathBuf, timeout: Option<u64>) -> Result<(u128, BigInt), ()> {
+    /// Compiles a CNF into a d-DNNF, returning the time taken, the model count and the resources
+    /// used.
+    ///
+    /// The d-DNNF is written to the dump file if given, its size being part of the result.
+    pub fn compile(
+        &self,
+        cnf: PathBuf,
+        timeout: Option<u64>,
+        dump: Option<&Path>,
+    ) -> Result<Compilation, D4Error> {
         debug!("Running d4 on: {:?}", cnf);
 
         if let Some(timeout) = timeout {
@@ -52,7 +196,7 @@ impl D4 {
         }
 
         // Create the d4 process and run it.
-        let mut run = Command::new(&self.0);
+        let mut run = Command::new(&self.path);
         run.args([
             "--input",
             cnf.to_str().expect("Failed to serialize cnf path."),
@@ -60,81 +204,85 @@ impl D4 {
             "ddnnf-compiler",
             "--partitioning-heuristic",
             "none",
-        ])
-        .stdout(Stdio::piped());
+        ]);
 
-        trace!("{:?}", run);
+        if let Some(dump) = dump {
+            run.arg("--dump-file").arg(dump);
+        }
 
-        // Start the d4 process and track the time.
-        let start = Instant::now();
-        let mut process = run.spawn().expect("Failed to spawn d4 process.");
+        let output =
+            process::run(&mut run, self.limits.with_time(timeout)).map_err(D4Error::Process)?;
 
-        if let Some(timeout) = timeout {
-            // Spawn a thread for the timeout.
-            let (timeout_sender, timeout_receiver) = channel();
-            thread::spawn(move || {
-                sleep(Duration::from_secs(timeout));
-                let _ = timeout_sender.send(());
+        // In case the timeout is reached, indicate a not finished operation.
+        if output.timed_out {
+            warn!("Timeout reached.");
+            return Ok(Compilation {
+                time: u128::MAX,
+                count: BigInt::ZERO,
+                usage: output.usage,
+                metrics: Metrics::default(),
             });
+        }
 
-            // Wait for either the d4 process or the timeout to finish.
-            loop {
-                // In case the process finises, continue.
-                if process
-                    .try_wait()
-                    .expect("Failed to check for d4 process.")
-                    .is_some()
-                {
-                    break;
-                }
-
-                let timeout_status = timeout_receiver.try_recv();
-
-                // In case the timeout is reached, indicate a not finished operation.
-                if let Ok(()) = timeout_status {
-                    warn!("Timeout reached.");
-                    process.kill().expect("Failed to kill d4 process.");
-                    return Ok((u128::MAX, BigInt::ZERO));
-                }
-
-                // Panic in case the timeout thread disconnected.
-                if let Err(TryRecvError::Disconnected) = timeout_status {
-                    panic!("Timeout process disconnected.");
-                }
-
-                yield_now();
-            }
+        // A process killed by a signal, e.g. by exceeding the memory limit, did not finish.
+        if output.status.signal().is_some() {
+            return Err(D4Error::from_output(&output));
+        }
+
+        // Extract the model count.
+        let Some(count) = parse_count(&String::from_utf8_lossy(&output.stdout)) else {
+            return Err(D4Error::from_output(&output));
+        };
+
+        if !output.status.success() {
+            debug!(
+                "d4 reported a model count but exited with {}.",
+                output.status
+            );
         }
 
-        let output = process
-            .wait_with_output()
-            .expect("Failed to wait for d4 process.");
+        info!("d4 took {} ms, model count: {}", output.time, count);
 
-        let duration = start.elapsed().as_millis();
+        let metrics = match dump {
+            Some(dump) => Nnf::read(dump).map_err(D4Error::Dump)?.metrics(),
+            None => Metrics::default(),
+        };
 
-        let mut last_line = from_utf8(&output.stdout)
-            .expect("Failed to read output from d4.")
-            .lines()
-            .last()
-            .expect("No output from d4.")
-            .split_whitespace();
+        Ok(Compilation {
+            time: output.time,
+            count,
+            usage: output.usage,
+            metrics,
+        })
+    }
+}
 
+#[cfg(test)]
+mod test {
+    use crate::d4::parse_count;
+    use num::BigInt;
+
+    #[test]
+    fn count() {
+        assert_eq!(parse_count("c comment\ns 34\n"), Some(BigInt::from(34)));
+        assert_eq!(parse_count("s 34\nc statistics\n"), Some(BigInt::from(34)));
         assert_eq!(
-            last_line.next(),
-            Some("s"),
-            "Last line of output should start with 's'."
+            parse_count("s 123456789012345678901234567890"),
+            "123456789012345678901234567890".parse().ok()
         );
+    }
 
-        // Extract the model count.
-        let count = BigInt::from_str(
-            last_line
-                .next()
-                .expect("Last line of output should contain the model count."),
-        )
-        .expect("Failed to read model count.");
-
-        info!("d4 took {} ms, model count: {}", duration, count);
+    #[test]
+    fn unsatisfiable() {
+        assert_eq!(parse_count("s UNSATISFIABLE\n"), Some(BigInt::ZERO));
+        assert_eq!(parse_count("s 0\n"), Some(BigInt::ZERO));
+    }
 
-        Ok((duration, count))
+    #[test]
+    fn missing() {
+        assert_eq!(parse_count(""), None);
+        assert_eq!(parse_count("c no solution line\n"), None);
+        assert_eq!(parse_count("s\n"), None);
+        assert_eq!(parse_count("s SATISFIABLE\n"), None);
     }
 }
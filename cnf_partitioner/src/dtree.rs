@@ -0,0 +1,82 @@
+use crate::batch::Deadline;
+use crate::partitioner::{Partitioner, PartitionerError};
+use crate::process::Limits;
+use crate::Args;
+use hypergraph::{Dtree, Hypergraph, Partition};
+use hypergraph_formats::Graph;
+use std::io;
+
+/// The result of building a dtree of the clauses of a CNF by recursive bisection.
+#[derive(Debug, Clone, Default)]
+pub struct Bisection {
+    /// The width of the dtree.
+    pub width: usize,
+    /// The size of the largest cutset of the dtree.
+    pub max_cutset: usize,
+    /// The amount of levels below the root of the dtree.
+    pub height: usize,
+    /// The wall-clock time taken for all partitioning in ms, `u128::MAX` in case the partitioner
+    /// reached the time limit, the affected clauses being split in halves instead.
+    pub time: u128,
+}
+
+/// Builds a dtree of the clauses of a CNF by recursively bisecting its dual hypergraph with a
+/// partitioner.
+///
+/// Each bisection partitions the hypergraph induced by the clauses to split into the blocks of the
+/// partitioner, the lower half of the blocks going to the left subtree and the others to the right
+/// one. Sets of at most as many clauses as blocks, or for which the partitioner reaches its time
+/// limit, are split in halves instead.
+pub fn bisect(
+    args: &Args,
+    graph: &Graph,
+    partitioner: &dyn Partitioner,
+    deadline: Deadline,
+) -> io::Result<(Dtree, Bisection)> {
+    let blocks = partitioner.blocks();
+    let mut time = 0u128;
+
+    let dtree = Hypergraph::from(graph).dtree(|vertices| -> io::Result<Partition> {
+        deadline.check()?;
+
+        if vertices.len() <= blocks {
+            return Ok(halves(vertices.len()));
+        }
+
+        let limits = Limits::new(deadline.limit(args.partitioner_timeout)?, args.memory_limit);
+        let (taken, _, partition) = match partitioner.run(&graph.induced(vertices), limits) {
+            Ok(result) => result,
+            // Keep building the dtree, marking the time as timed out.
+            Err(PartitionerError::Timeout) => {
+                time = u128::MAX;
+                return Ok(halves(vertices.len()));
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        time = time.saturating_add(taken);
+
+        Ok(partition
+            .iter()
+            .map(|&block| usize::from(2 * block >= blocks))
+            .collect::<Vec<usize>>()
+            .into())
+    })?;
+
+    let bisection = Bisection {
+        width: dtree.width(),
+        max_cutset: dtree.max_cutset(),
+        height: dtree.height(),
+        time,
+    };
+
+    Ok((dtree, bisection))
+}
+
+/// Splits the given amount of vertices in halves by their order.
+fn halves(vertices: usize) -> Partition {
+    (0..vertices)
+        .map(|index| usize::from(2 * index >= vertices))
+        .collect::<Vec<usize>>()
+        .into()
+}
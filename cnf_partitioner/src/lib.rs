@@ -0,0 +1,19 @@
+pub mod args;
+pub mod assignment;
+pub mod batch;
+pub mod cnf;
+pub mod components;
+pub mod compose;
+pub mod cubes;
+pub mod d4;
+pub mod dtree;
+pub mod output;
+pub mod partitioner;
+pub mod pipeline;
+pub mod preprocess;
+pub mod process;
+pub mod recursive;
+pub mod refine;
+pub mod selection;
+
+pub use args::Args;
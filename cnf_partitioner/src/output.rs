@@ -1,14 +1,28 @@
-use hypergraph_formats::cnf::VariableHeuristic;
-use num::BigInt;
+use crate::assignment::Strategy;
+use crate::components::Decomposition;
+use crate::compose::Composition;
+use crate::cubes::Cubes;
+use crate::d4::Compilation;
+use crate::dtree::Bisection;
+use crate::preprocess::Preprocessing;
+use crate::process::Usage;
+use crate::recursive::Trace;
+use crate::refine::Refinement;
+use crate::selection::{Criterion, Selection, Trajectory};
+use ddnnf::Metrics;
+use hypergraph_formats::cnf::{Literal, VariableHeuristic};
+use num::{BigInt, ToPrimitive};
+use std::collections::BTreeMap;
+use strum::Display;
 
 /// A collection of run results being this programs output.
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Output(Vec<Run>);
 
 impl Output {
     /// Creates an empty output.
     pub fn new() -> Output {
-        Self(Vec::new())
+        Self::default()
     }
 
     /// Adds a run.
@@ -16,6 +30,30 @@ impl Output {
         self.0.push(run);
     }
 
+    /// The amount of runs.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no runs were added.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Records the trajectory of selecting the amount of blocks in each run of a configuration.
+    pub fn select_blocks(&mut self, selection: &Selection) {
+        self.0.iter_mut().for_each(|run| {
+            run.trajectory = selection
+                .trajectory(
+                    &run.partitioner,
+                    run.variable_heuristic,
+                    run.epsilon,
+                    run.seed,
+                )
+                .cloned();
+        });
+    }
+
     /// Serializes the output as CSV.
     pub fn csv(&self) -> String {
         let mut output = String::new();
@@ -27,6 +65,76 @@ impl Output {
 
         output
     }
+
+    /// The CSV header describing the statistics over runs with different seeds.
+    pub const fn statistics_csv_header() -> &'static str {
+        "instance,partitioner,heuristic,blocks,epsilon,runs,cut_mean,cut_min,cut_max,cut_stddev\n"
+    }
+
+    /// Serializes statistics over the cut sizes of runs only differing in their seed as CSV.
+    pub fn statistics_csv(&self) -> String {
+        let mut groups: BTreeMap<(&str, &str, String, usize, String), Vec<usize>> = BTreeMap::new();
+
+        self.0.iter().for_each(|run| {
+            groups
+                .entry((
+                    &run.instance,
+                    &run.partitioner,
+                    run.variable_heuristic.to_string(),
+                    run.blocks,
+                    run.epsilon.to_string(),
+                ))
+                .or_default()
+                .push(run.cut_size);
+        });
+
+        let mut output = String::new();
+
+        groups.iter().for_each(
+            |((instance, partitioner, heuristic, blocks, epsilon), cuts)| {
+                let runs = cuts.len() as f64;
+                let mean = cuts.iter().sum::<usize>() as f64 / runs;
+
+                // Use the sample standard deviation, being `0` for a single run.
+                let variance = if cuts.len() > 1 {
+                    cuts.iter()
+                        .map(|&cut| (cut as f64 - mean).powi(2))
+                        .sum::<f64>()
+                        / (runs - 1.0)
+                } else {
+                    0.0
+                };
+
+                output.push_str(&format!(
+                    "{},{},{},{},{},{},{},{},{},{}\n",
+                    instance,
+                    partitioner,
+                    heuristic,
+                    blocks,
+                    epsilon,
+                    cuts.len(),
+                    mean,
+                    cuts.iter().min().unwrap(),
+                    cuts.iter().max().unwrap(),
+                    variance.sqrt()
+                ));
+            },
+        );
+
+        output
+    }
+}
+
+/// How a run ended.
+#[derive(Debug, Display, Copy, Clone, PartialEq)]
+#[strum(serialize_all = "lowercase")]
+pub enum Status {
+    /// All compilations finished.
+    Finished,
+    /// The CNF has no models, so it was not split.
+    Unsatisfiable,
+    /// At least one compilation reached the time limit.
+    Timeout,
 }
 
 /// A run of a partitioner
@@ -36,63 +144,235 @@ pub struct Run {
     partitioner: String,
     variable_heuristic: VariableHeuristic,
     blocks: usize,
+    epsilon: f64,
+    imbalance: f64,
+    seed: u64,
     cut_size: usize,
     time_partitioning: u128,
+    usage_partitioning: Usage,
     time_solving: Vec<u128>,
-    time_original: u128,
-    time_conditioned: u128,
+    usage_solving: Vec<Usage>,
+    metrics_solving: Vec<Metrics>,
+    original: Compilation,
+    conditioned: Compilation,
     count: BigInt,
-    count_conditioned: BigInt,
-    count_original: BigInt,
+    free_variables: usize,
+    empty_blocks: usize,
+    preprocessing: Preprocessing,
+    decomposition: Decomposition,
+    composition: Composition,
+    recursion: Option<Trace>,
+    refinement: Refinement,
+    bisection: Bisection,
+    strategy: Strategy,
+    assignment: Vec<Literal>,
+    sample_times: Vec<u128>,
+    cubes: Option<Cubes>,
+    trajectory: Option<Trajectory>,
+    unsatisfiable: bool,
 }
 
 impl Run {
     /// Creates a new run.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         instance: String,
         partitioner: &str,
         variable_heuristic: VariableHeuristic,
         blocks: usize,
+        epsilon: f64,
+        imbalance: f64,
+        seed: u64,
         cut_size: usize,
-        time_original: u128,
-        count_original: BigInt,
-        time_conditioned: u128,
-        count_conditioned: BigInt,
+        original: Compilation,
+        conditioned: Compilation,
         time_partitioning: u128,
+        usage_partitioning: Usage,
     ) -> Self {
         Self {
             instance,
             partitioner: String::from(partitioner),
             variable_heuristic,
             blocks,
+            epsilon,
+            imbalance,
+            seed,
             cut_size,
-            time_original,
-            count_original,
-            time_conditioned,
-            count_conditioned,
+            original,
+            conditioned,
             time_partitioning,
+            usage_partitioning,
             count: BigInt::from(1),
             ..Default::default()
         }
     }
 
     /// Adds the result of solving a part of the split CNF.
-    pub fn add_part(&mut self, time: u128, count: BigInt) {
-        self.time_solving.push(time);
-        self.count *= count;
+    pub fn add_part(&mut self, compilation: Compilation) {
+        self.time_solving.push(compilation.time);
+        self.usage_solving.push(compilation.usage);
+        self.metrics_solving.push(compilation.metrics);
+        self.count *= compilation.count;
+    }
+
+    /// Sets the amount of blocks the partition assigned no clauses to.
+    pub fn set_empty_blocks(&mut self, empty_blocks: usize) {
+        self.empty_blocks = empty_blocks;
+    }
+
+    /// Sets the result of refining the partition.
+    pub fn set_refinement(&mut self, refinement: Refinement) {
+        self.refinement = refinement;
+    }
+
+    /// Sets the result of building a dtree by recursive bisection.
+    pub fn set_bisection(&mut self, bisection: Bisection) {
+        self.bisection = bisection;
+    }
+
+    /// Sets the strategy used for choosing the assignment conditioned on and the assignment itself.
+    pub fn set_assignment(&mut self, strategy: Strategy, assignment: Vec<Literal>) {
+        self.strategy = strategy;
+        self.assignment = assignment;
+    }
+
+    /// Adds the total time of compiling the split CNFs conditioned on a further sampled assignment.
+    pub fn add_sample(&mut self, time: u128) {
+        self.sample_times.push(time);
+    }
+
+    /// Sets the amount of satisfying cut assignments.
+    pub fn set_cubes(&mut self, cubes: Cubes) {
+        self.cubes = Some(cubes);
+    }
+
+    /// Estimates the time of compiling the split CNFs for every satisfying cut assignment by the
+    /// mean time for the compiled ones, `u128::MAX` in case of a timeout.
+    fn estimated_time(&self, cubes: &Cubes) -> BigInt {
+        let times = self.split_times();
+
+        if times.contains(&u128::MAX) {
+            return BigInt::from(u128::MAX);
+        }
+
+        let sum: BigInt = times.iter().map(|&time| BigInt::from(time)).sum();
+
+        &cubes.count * sum / times.len()
+    }
+
+    /// The value of a criterion for selecting the amount of blocks, the estimated time being
+    /// `u128::MAX` without counting the satisfying cut assignments.
+    pub fn criterion(&self, criterion: Criterion) -> f64 {
+        match criterion {
+            Criterion::Cut => self.cut_size as f64,
+            Criterion::Time => self.split_time() as f64,
+            Criterion::Estimated => match &self.cubes {
+                Some(cubes) => self.estimated_time(cubes).to_f64().unwrap_or(f64::MAX),
+                None => u128::MAX as f64,
+            },
+        }
+    }
+
+    /// The total time of compiling the split CNFs for the assignment conditioned on, `u128::MAX`
+    /// in case of a timeout.
+    fn split_time(&self) -> u128 {
+        self.time_solving
+            .iter()
+            .fold(0, |sum: u128, &time| sum.saturating_add(time))
+    }
+
+    /// The total times of compiling the split CNFs for the assignment conditioned on followed by
+    /// the ones of further sampled assignments in ascending order, `u128::MAX` in case of a
+    /// timeout.
+    fn split_times(&self) -> Vec<u128> {
+        let mut times = self.sample_times.clone();
+        times.push(self.split_time());
+        times.sort();
+        times
+    }
+
+    /// Marks the CNF as unsatisfiable, leaving it unsplit.
+    pub fn set_unsatisfiable(&mut self) {
+        self.unsatisfiable = true;
+    }
+
+    /// Determines how the run ended.
+    pub fn status(&self) -> Status {
+        let timed_out = self.time_partitioning == u128::MAX
+            || self.original.timed_out()
+            || self.conditioned.timed_out()
+            || self.time_solving.contains(&u128::MAX)
+            || self.composition.timed_out()
+            || self.recursion.as_ref().is_some_and(Trace::timed_out);
+
+        if self.unsatisfiable {
+            Status::Unsatisfiable
+        } else if timed_out {
+            Status::Timeout
+        } else {
+            Status::Finished
+        }
+    }
+
+    /// Sets how the instance was preprocessed.
+    pub fn set_preprocessing(&mut self, preprocessing: Preprocessing) {
+        self.preprocessing = preprocessing;
+    }
+
+    /// Sets the decomposition of the instance into components.
+    pub fn set_decomposition(&mut self, decomposition: Decomposition) {
+        self.decomposition = decomposition;
+    }
+
+    /// Sets the d-DNNF composed of the parts.
+    pub fn set_composition(&mut self, composition: Composition) {
+        self.composition = composition;
+    }
+
+    /// Sets the tree of counting the parts recursively.
+    pub fn set_recursion(&mut self, trace: Trace) {
+        self.recursion = Some(trace);
+    }
+
+    /// Accounts for variables occurring in none of the parts, each doubling the model count.
+    pub fn add_free_variables(&mut self, free_variables: usize) {
+        self.free_variables += free_variables;
+        self.count <<= free_variables;
     }
 
     /// Checks the result after all partial runs for correctness.
+    ///
+    /// Only finished runs are checked as others lack some of the counts.
     pub fn check(&self) {
+        if self.status() != Status::Finished {
+            return;
+        }
+
         assert_eq!(
-            self.count_conditioned, self.count,
+            self.conditioned.count, self.count,
             "The counts between the conditioned and split CNFs (collectively) should be equal."
         );
     }
 
     /// The output CSV header describing the contents of runs.
     pub const fn csv_header() -> &'static str {
-        "instance,partitioner,heuristic,blocks,cut_size,time_original,time_conditioned,count_original,count_conditioned,time_split,time_sum,time_partitioning"
+        "instance,partitioner,heuristic,blocks,cut_size,time_original,time_conditioned,count_original,count_conditioned,time_split,time_sum,time_partitioning,\
+nodes_original,edges_original,depth_original,decisions_original,\
+nodes_conditioned,edges_conditioned,depth_conditioned,decisions_conditioned,\
+nodes_split,edges_split,depth_split,decisions_split,\
+time_user_original,time_system_original,memory_original,\
+time_user_conditioned,time_system_conditioned,memory_conditioned,\
+time_user_split,time_system_split,memory_split,\
+time_user_partitioning,time_system_partitioning,memory_partitioning,seed,epsilon,imbalance,free_variables,empty_blocks,\
+preprocessing,time_preprocessing,preprocessing_factor,\
+components,component_sizes,count_components,time_components,status,\
+assignments_composed,count_composed,time_composed,\
+count_recursive,time_recursive,height_recursive,leaves_recursive,\
+cut_before_refinement,cut_after_refinement,refinement_moves,\
+width_dtree,max_cutset_dtree,height_dtree,time_dtree,\
+assignment_strategy,assignment,samples,time_split_min,time_split_median,time_split_max,\
+cubes,cubes_exact,time_estimated,\
+blocks_chosen,blocks_trajectory"
     }
 
     /// Serializes a run into a CSV row.
@@ -104,17 +384,142 @@ impl Run {
             self.variable_heuristic,
             self.blocks,
             self.cut_size,
-            self.time_original,
-            self.time_conditioned,
-            self.count_original,
-            self.count_conditioned,
-            self.time_solving
-                .iter()
-                .map(u128::to_string)
-                .collect::<Vec<String>>()
-                .join(";"),
-            self.time_solving.iter().sum::<u128>(),
+            self.original.time,
+            self.conditioned.time,
+            self.original.count,
+            self.conditioned.count,
+            join(self.time_solving.iter()),
+            self.split_time(),
             self.time_partitioning,
         ));
+
+        for metrics in [&self.original.metrics, &self.conditioned.metrics] {
+            output.push_str(&format!(
+                ",{},{},{},{}",
+                metrics.nodes, metrics.edges, metrics.depth, metrics.decisions
+            ));
+        }
+
+        output.push_str(&format!(
+            ",{},{},{},{}",
+            join(self.metrics_solving.iter().map(|metrics| metrics.nodes)),
+            join(self.metrics_solving.iter().map(|metrics| metrics.edges)),
+            join(self.metrics_solving.iter().map(|metrics| metrics.depth)),
+            join(self.metrics_solving.iter().map(|metrics| metrics.decisions)),
+        ));
+
+        for usage in [&self.original.usage, &self.conditioned.usage] {
+            output.push_str(&format!(
+                ",{},{},{}",
+                usage.time_user, usage.time_system, usage.memory
+            ));
+        }
+
+        output.push_str(&format!(
+            ",{},{},{}",
+            join(self.usage_solving.iter().map(|usage| usage.time_user)),
+            join(self.usage_solving.iter().map(|usage| usage.time_system)),
+            join(self.usage_solving.iter().map(|usage| usage.memory)),
+        ));
+
+        output.push_str(&format!(
+            ",{},{},{}",
+            self.usage_partitioning.time_user,
+            self.usage_partitioning.time_system,
+            self.usage_partitioning.memory
+        ));
+
+        output.push_str(&format!(
+            ",{},{},{},{},{}",
+            self.seed, self.epsilon, self.imbalance, self.free_variables, self.empty_blocks
+        ));
+
+        output.push_str(&format!(
+            ",{},{},{}",
+            self.preprocessing.method, self.preprocessing.time, self.preprocessing.factor
+        ));
+
+        output.push_str(&format!(
+            ",{},{},{},{}",
+            self.decomposition.sizes.len(),
+            join(self.decomposition.sizes.iter()),
+            self.decomposition.count,
+            self.decomposition.time
+        ));
+
+        output.push_str(&format!(",{}", self.status()));
+
+        output.push_str(&format!(
+            ",{},{},{}",
+            self.composition.assignments, self.composition.count, self.composition.time
+        ));
+
+        match &self.recursion {
+            Some(trace) => output.push_str(&format!(
+                ",{},{},{},{}",
+                trace.count,
+                trace.time(),
+                trace.height(),
+                trace.leaves()
+            )),
+            None => output.push_str(",0,0,0,0"),
+        }
+
+        output.push_str(&format!(
+            ",{},{},{}",
+            self.refinement.cut_before, self.refinement.cut_after, self.refinement.moves
+        ));
+
+        output.push_str(&format!(
+            ",{},{},{},{}",
+            self.bisection.width,
+            self.bisection.max_cutset,
+            self.bisection.height,
+            self.bisection.time
+        ));
+
+        let times = self.split_times();
+
+        output.push_str(&format!(
+            ",{},{},{},{},{},{}",
+            self.strategy,
+            join(self.assignment.iter().map(Literal::value)),
+            times.len(),
+            times[0],
+            times[times.len() / 2],
+            times[times.len() - 1]
+        ));
+
+        match &self.cubes {
+            Some(cubes) => output.push_str(&format!(
+                ",{},{},{}",
+                cubes.count,
+                cubes.exact,
+                self.estimated_time(cubes)
+            )),
+            None => output.push_str(",0,false,0"),
+        }
+
+        match &self.trajectory {
+            Some(trajectory) => output.push_str(&format!(
+                ",{},{}",
+                trajectory.chosen().unwrap_or_default(),
+                join(
+                    trajectory
+                        .points
+                        .iter()
+                        .map(|(blocks, value)| format!("{blocks}:{value}"))
+                )
+            )),
+            None => output.push_str(",0,"),
+        }
     }
 }
+
+/// Joins multiple values into a single CSV cell separated by `;`.
+fn join<T: ToString>(values: impl Iterator<Item = T>) -> String {
+    values
+        .map(|value| value.to_string())
+        .collect::<Vec<String>>()
+        .join(";")
+}
@@ -1,20 +1,26 @@
+pub mod hmetis;
 pub mod kahypar;
 pub mod mtkahypar;
+pub mod native;
 pub mod patoh;
-pub mod random;
 
+use crate::d4::tail;
+use crate::partitioner::hmetis::HMetis;
 use crate::partitioner::kahypar::KaHyPar;
-use crate::partitioner::random::Random;
-use crate::{Args, Partition};
+use crate::partitioner::native::{Algorithm, Native};
+use crate::process::{self, Limits, Usage};
+use crate::Args;
+use hypergraph::Partition;
 use hypergraph_formats::Graph;
-use log::{error, info, trace};
+use log::{error, info, warn};
 use mtkahypar::MtKaHyPar;
 use patoh::PaToH;
-use std::io::Write;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io::{self, Write};
 use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
-use std::process::Command;
-use std::time::Instant;
+use std::process::{Command, ExitStatus};
 use tempfile::NamedTempFile;
 
 pub trait Partitioner {
@@ -26,6 +32,65 @@ pub trait Partitioner {
 
     fn blocks(&self) -> usize;
 
+    /// Returns the allowed imbalance.
+    fn epsilon(&self) -> f64;
+
+    /// Returns the seed used for randomization.
+    fn seed(&self) -> u64;
+
+    /// Runs the partitioner within the given limits.
+    ///
+    /// Returns the wall-clock time taken, the resources used and the partition.
+    fn run(
+        &self,
+        graph: &Graph,
+        limits: Limits,
+    ) -> Result<(u128, Usage, Partition), PartitionerError>;
+}
+
+/// Reasons for a partitioner run to fail.
+#[derive(Debug)]
+pub enum PartitionerError {
+    /// The process could not be run.
+    Process(io::Error),
+    /// The time limit was reached.
+    Timeout,
+    /// The process did not finish successfully, e.g. by exceeding the memory limit.
+    Exit { status: ExitStatus, stderr: String },
+}
+
+impl Display for PartitionerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartitionerError::Process(error) => write!(f, "failed to run partitioner: {error}"),
+            PartitionerError::Timeout => write!(f, "partitioner reached the time limit"),
+            PartitionerError::Exit { status, stderr } => {
+                write!(f, "partitioner failed with {status}, stderr: {stderr:?}")
+            }
+        }
+    }
+}
+
+impl Error for PartitionerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PartitionerError::Process(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<PartitionerError> for io::Error {
+    fn from(value: PartitionerError) -> Self {
+        match value {
+            PartitionerError::Timeout => io::Error::new(io::ErrorKind::TimedOut, value),
+            value => io::Error::other(value),
+        }
+    }
+}
+
+/// A partitioner running as an external process, exchanging the graph and partition via files.
+pub trait ExternalPartitioner: Partitioner {
     /// Creates a string representation for the given graph.
     fn serialize_graph(&self, graph: &Graph) -> String;
 
@@ -35,8 +100,14 @@ pub trait Partitioner {
     /// Calculates the path of the resulting partiton output file.
     fn output_file(&self, graph: &Path) -> PathBuf;
 
-    /// Runs the partitioner.
-    fn run(&self, graph: &Graph) -> Result<(u128, Partition), ()> {
+    /// Runs the partitioner process within the given limits.
+    ///
+    /// Returns the wall-clock time taken, the resources used and the partition.
+    fn run_external(
+        &self,
+        graph: &Graph,
+        limits: Limits,
+    ) -> Result<(u128, Usage, Partition), PartitionerError> {
         // Write the graph to a temporary file.
         let mut graph_file =
             NamedTempFile::new().expect("Failed to create temporary file for graph.");
@@ -52,33 +123,41 @@ pub trait Partitioner {
         let mut run = self.create_run(&graph_file);
 
         info!("Running {} ...", self.name_full());
-        trace!("{:?}", run);
 
-        // Run the partitioner while tracking the time taken.
-        let start = Instant::now();
-        let output = run.output();
-        let duration = start.elapsed().as_millis();
+        // Run the partitioner while tracking the time and resources taken.
+        let output = process::run(&mut run, limits);
 
         // Simply abort this function in case of error.
-        if let Err(error) = output {
+        let output = output.map_err(|error| {
             error!("Failed to run {}: {}", self.name_full(), error);
-            return Err(());
-        }
+            PartitionerError::Process(error)
+        })?;
 
-        let output = output.unwrap();
+        // Also abort in case the time limit was reached ...
+        if output.timed_out {
+            warn!("{} reached the time limit.", self.name_full());
+            return Err(PartitionerError::Timeout);
+        }
 
-        // Also abort in case the process did not finish successfully.
+        // ... or the process did not finish successfully.
         if !output.status.success() {
             error!("Failed to run {}:", self.name_full());
-            error!("stderr: {}", String::from_utf8(output.stderr).unwrap());
-            error!("stdout: {}", String::from_utf8(output.stdout).unwrap());
-            return Err(());
+            error!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+            error!("stdout: {}", String::from_utf8_lossy(&output.stdout));
+            return Err(PartitionerError::Exit {
+                status: output.status,
+                stderr: tail(&output.stderr),
+            });
         }
 
         // Inform about the time taken and where the output file is.
-        info!("{} took {} ms.", self.name_full(), duration);
+        info!("{} took {} ms.", self.name_full(), output.time);
 
-        Ok((duration, self.output_file(&graph_file).into()))
+        Ok((
+            output.time,
+            output.usage,
+            self.output_file(&graph_file).into(),
+        ))
     }
 }
 
@@ -86,25 +165,50 @@ pub trait Partitioner {
 pub fn instantiate(args: &Args) -> Vec<Box<dyn Partitioner>> {
     let mut instances: Vec<Box<dyn Partitioner>> = Vec::new();
 
-    for blocks in calculate_blocks(args) {
+    let epsilons = calculate_epsilons(args);
+
+    // Create the matrix of all block, epsilon and seed combinations, noting whether the epsilon
+    // and the seed are the first ones.
+    let configurations = calculate_blocks(args).flat_map(|blocks| {
+        epsilons
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, &epsilon)| {
+                calculate_seeds(args)
+                    .enumerate()
+                    .map(move |(repetition, seed)| {
+                        (blocks, epsilon, index == 0, seed, repetition == 0)
+                    })
+            })
+    });
+
+    for (blocks, epsilon, first_epsilon, seed, first_seed) in configurations {
         if args.kahypar_enable {
-            if let Some(path) = args.kahypar_path.clone() {
+            if let (Some(path), Some(epsilon)) = (
+                args.kahypar_path.clone(),
+                tool_epsilon(args.kahypar_epsilon, epsilon, first_epsilon),
+            ) {
                 instances.push(Box::new(KaHyPar::new(
                     path,
                     blocks,
                     args.kahypar_metric,
-                    args.kahypar_epsilon.clone(),
+                    epsilon,
+                    seed,
                 )));
             }
         }
 
         if args.mtkahypar_enable {
-            if let Some(path) = args.mtkahypar_path.clone() {
+            if let (Some(path), Some(epsilon)) = (
+                args.mtkahypar_path.clone(),
+                tool_epsilon(args.mtkahypar_epsilon, epsilon, first_epsilon),
+            ) {
                 instances.push(Box::new(MtKaHyPar::new(
                     path,
                     blocks,
                     args.mtkahypar_metric,
-                    args.mtkahypar_epsilon.clone(),
+                    epsilon,
+                    seed,
                 )));
             }
         }
@@ -116,43 +220,227 @@ pub fn instantiate(args: &Args) -> Vec<Box<dyn Partitioner>> {
                     blocks,
                     args.patoh_metric,
                     args.patoh_preset,
+                    epsilon,
+                    seed,
                 )));
             }
         }
 
-        if args.random_enable {
-            if let Some(path) = args.rust_path.clone() {
-                instances.push(Box::new(Random::new(path, blocks)));
+        // hMETIS takes no seed, and no epsilon either if its `UBfactor` is given.
+        let hmetis_once = first_seed && (first_epsilon || args.hmetis_ubfactor.is_none());
+
+        if args.hmetis_enable && hmetis_once {
+            if let Some(path) = args.hmetis_path.clone() {
+                instances.push(Box::new(HMetis::new(
+                    path,
+                    args.hmetis_variant,
+                    blocks,
+                    epsilon,
+                    seed,
+                    hmetis::Options {
+                        ubfactor: args.hmetis_ubfactor,
+                        runs: args.hmetis_runs,
+                        coarsening: args.hmetis_ctype,
+                        refinement: args.hmetis_rtype,
+                        metric: args.hmetis_metric,
+                    },
+                )));
             }
         }
+
+        for algorithm in native_algorithms(args) {
+            // Only the random algorithm depends on epsilon and the seed, the others would repeat
+            // identical runs.
+            if algorithm != Algorithm::Random && !(first_epsilon && first_seed) {
+                continue;
+            }
+
+            instances.push(Box::new(Native::new(algorithm, blocks, epsilon, seed)));
+        }
     }
 
     instances
 }
 
+/// The native algorithms to run, including the random one if enabled by its legacy option.
+fn native_algorithms(args: &Args) -> Vec<Algorithm> {
+    let mut algorithms = args.native.clone();
+
+    if args.random_enable && !algorithms.contains(&Algorithm::Random) {
+        algorithms.push(Algorithm::Random);
+    }
+
+    algorithms
+}
+
 /// Calculates the range of blocks to consider based on the given arguments.
 pub fn calculate_blocks(args: &Args) -> RangeInclusive<usize> {
-    if args.blocks.is_some() {
-        assert!(
-            args.blocks_start.is_none() && args.blocks_end.is_none(),
-            "Either blocks or blocks_start and blocks_end must be specified."
+    match (args.blocks, args.blocks_start, args.blocks_end) {
+        (Some(blocks), None, None) => {
+            assert!(blocks >= 2);
+            blocks..=blocks
+        }
+        (None, Some(start), Some(end)) => {
+            assert!(start <= end);
+            start..=end
+        }
+        (None, None, Some(end)) if args.auto_blocks.is_some() => 2..=end,
+        _ => panic!("Either blocks or blocks_start and blocks_end must be specified."),
+    }
+}
+
+/// Calculates the epsilons (imbalance parameters) to consider based on the given arguments.
+///
+/// Either a list of epsilons or a range with a step size can be given, defaulting to `0.1`.
+pub fn calculate_epsilons(args: &Args) -> Vec<f64> {
+    match (
+        args.epsilon.is_empty(),
+        args.epsilon_start,
+        args.epsilon_end,
+        args.epsilon_step,
+    ) {
+        (true, None, None, None) => vec![0.1],
+        (false, None, None, None) => args.epsilon.clone(),
+        (true, Some(start), Some(end), Some(step)) => {
+            assert!(start <= end && step > 0.0);
+
+            // Calculate the amount of steps beforehand to avoid accumulating floating point errors.
+            let steps = ((end - start) / step + 1e-9).floor() as usize;
+
+            (0..=steps)
+                // Round to keep the values representable in the output file names of partitioners.
+                .map(|index| ((start + index as f64 * step) * 1e6).round() / 1e6)
+                .collect()
+        }
+        _ => panic!(
+            "Either epsilon or epsilon_start, epsilon_end and epsilon_step must be specified."
+        ),
+    }
+}
+
+/// The epsilon of a tool with its own epsilon overriding the swept ones.
+///
+/// Such a tool only runs for the first swept epsilon, returning `None` for the others.
+fn tool_epsilon(own: Option<f64>, epsilon: f64, first: bool) -> Option<f64> {
+    match own {
+        Some(own) => first.then_some(own),
+        None => Some(epsilon),
+    }
+}
+
+/// Calculates the seeds to use based on the given arguments, one per repetition.
+pub fn calculate_seeds(args: &Args) -> RangeInclusive<u64> {
+    assert!(
+        args.repetitions >= 1,
+        "At least one repetition is required."
+    );
+
+    let last = args
+        .seed
+        .checked_add(args.repetitions - 1)
+        .expect("The seeds of all repetitions must not exceed the largest possible seed.");
+
+    args.seed..=last
+}
+
+#[cfg(test)]
+mod test {
+    use crate::partitioner::{calculate_epsilons, calculate_seeds, instantiate, tool_epsilon};
+    use crate::Args;
+    use clap::Parser;
+
+    fn args(arguments: &[&str]) -> Args {
+        Args::parse_from(
+            ["cnf_partitioner", "--d4-path", "d4", "-b", "2"]
+                .iter()
+                .chain(arguments)
+                .chain(&["test.cnf"]),
+        )
+    }
+
+    #[test]
+    fn seeds() {
+        let repeated = args(&["--seed", "3", "--repetitions", "2"]);
+        assert_eq!(calculate_seeds(&repeated), 3..=4);
+
+        // The largest seed is fine for a single repetition.
+        let max = u64::MAX.to_string();
+        assert_eq!(
+            calculate_seeds(&args(&["--seed", &max])),
+            u64::MAX..=u64::MAX
         );
+    }
+
+    #[test]
+    fn tool_epsilons() {
+        let swept = args(&["-e", "0.1,0.2", "--kahypar-epsilon", "0.03"]);
+        let epsilons = calculate_epsilons(&swept);
+
+        // The own epsilon of a tool is used once, the others sweeping all epsilons.
+        let kahypar: Vec<Option<f64>> = epsilons
+            .iter()
+            .enumerate()
+            .map(|(index, &epsilon)| tool_epsilon(swept.kahypar_epsilon, epsilon, index == 0))
+            .collect();
+        let mtkahypar: Vec<Option<f64>> = epsilons
+            .iter()
+            .enumerate()
+            .map(|(index, &epsilon)| tool_epsilon(swept.mtkahypar_epsilon, epsilon, index == 0))
+            .collect();
 
-        assert!(args.blocks.unwrap() >= 2);
+        assert_eq!(kahypar, [Some(0.03), None]);
+        assert_eq!(mtkahypar, [Some(0.1), Some(0.2)]);
     }
 
-    if args.blocks.is_none() {
-        assert!(
-            args.blocks_start.is_some() && args.blocks_end.is_some(),
-            "Either blocks or blocks_start and blocks_end must be specified."
+    #[test]
+    fn random_enable() {
+        let names = |arguments: &[&str]| -> Vec<&'static str> {
+            instantiate(&args(arguments))
+                .iter()
+                .map(|partitioner| partitioner.name_short())
+                .collect()
+        };
+
+        assert_eq!(
+            names(&["--random-enable", "--rust-path", "rust"]),
+            ["Random"]
+        );
+        assert_eq!(
+            names(&["--native", "random", "--random-enable"]),
+            ["Random"]
+        );
+        assert_eq!(
+            names(&["--native", "bfs", "--random-enable"]),
+            ["BFS", "Random"]
+        );
+    }
+
+    #[test]
+    fn deterministic_once() {
+        let names = |arguments: &[&str]| -> Vec<String> {
+            instantiate(&args(arguments))
+                .iter()
+                .map(|partitioner| partitioner.name_full())
+                .collect()
+        };
+
+        // Only the random algorithm is repeated per seed.
+        assert_eq!(
+            names(&["--native", "bfs,random", "--repetitions", "2"]),
+            ["BFS", "Random (seed: 0)", "Random (seed: 1)"]
         );
 
-        assert!(args.blocks_start.unwrap() <= args.blocks_end.unwrap());
+        // Nor do the deterministic ones depend on the swept epsilons.
+        assert_eq!(
+            names(&["--native", "dfs,random", "-e", "0.1,0.2"]),
+            ["DFS", "Random (seed: 0)", "Random (seed: 0)"]
+        );
     }
 
-    if args.blocks.is_some() {
-        args.blocks.unwrap()..=args.blocks.unwrap()
-    } else {
-        args.blocks_start.unwrap()..=args.blocks_end.unwrap()
+    #[test]
+    #[should_panic(expected = "largest possible seed")]
+    fn seeds_overflow() {
+        let max = u64::MAX.to_string();
+        calculate_seeds(&args(&["--seed", &max, "--repetitions", "2"]));
     }
 }
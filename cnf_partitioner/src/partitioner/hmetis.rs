@@ -0,0 +1,261 @@
+use super::{ExternalPartitioner, Partitioner, PartitionerError};
+use crate::process::{Limits, Usage};
+use clap::ValueEnum;
+use hypergraph::Partition;
+use hypergraph_formats::hmetis::ToStringHMETIS;
+use hypergraph_formats::Graph;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use strum::Display;
+
+pub struct HMetis {
+    path: PathBuf,
+    variant: Variant,
+    blocks: usize,
+    epsilon: f64,
+    seed: u64,
+    options: Options,
+}
+
+/// The hMETIS executables, differing in their partitioning approach.
+#[derive(Debug, Display, Copy, Clone, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Variant {
+    /// Recursive bisection using default parameters.
+    Shmetis,
+    /// Recursive bisection.
+    Hmetis,
+    /// Direct k-way partitioning.
+    Khmetis,
+}
+
+impl Variant {
+    fn name(&self) -> &'static str {
+        match self {
+            Variant::Shmetis => "shMETIS",
+            Variant::Hmetis => "hMETIS",
+            Variant::Khmetis => "khMETIS",
+        }
+    }
+}
+
+/// The scheme used for grouping vertices during coarsening (`CType`).
+#[derive(Debug, Display, Copy, Clone, ValueEnum)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CoarseningType {
+    HybridFirstChoice,
+    FirstChoice,
+    GreedyFirstChoice,
+    Hyperedge,
+    Edge,
+}
+
+impl CoarseningType {
+    pub fn as_arg(&self) -> &str {
+        match self {
+            CoarseningType::HybridFirstChoice => "1",
+            CoarseningType::FirstChoice => "2",
+            CoarseningType::GreedyFirstChoice => "3",
+            CoarseningType::Hyperedge => "4",
+            CoarseningType::Edge => "5",
+        }
+    }
+}
+
+/// The refinement algorithm used during uncoarsening (`RType`), only used by `hmetis`.
+#[derive(Debug, Display, Copy, Clone, ValueEnum)]
+#[strum(serialize_all = "kebab-case")]
+pub enum RefinementType {
+    Fm,
+    OneWayFm,
+    EarlyExitFm,
+}
+
+impl RefinementType {
+    pub fn as_arg(&self) -> &str {
+        match self {
+            RefinementType::Fm => "1",
+            RefinementType::OneWayFm => "2",
+            RefinementType::EarlyExitFm => "3",
+        }
+    }
+}
+
+/// The objective to minimize (`OType`), only used by `khmetis`.
+#[derive(Debug, Display, Copy, Clone, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Metric {
+    Cut,
+    Soed,
+}
+
+impl Metric {
+    pub fn as_arg(&self) -> &str {
+        match self {
+            Metric::Cut => "1",
+            Metric::Soed => "2",
+        }
+    }
+}
+
+/// Parameters of `hmetis` and `khmetis`, `shmetis` uses its defaults.
+#[derive(Debug, Copy, Clone)]
+pub struct Options {
+    /// Overrides the `UBfactor` otherwise derived from epsilon.
+    pub ubfactor: Option<u32>,
+    /// The amount of bisections or partitions to compute, keeping the best (`Nruns`).
+    pub runs: usize,
+    pub coarsening: CoarseningType,
+    pub refinement: RefinementType,
+    pub metric: Metric,
+}
+
+impl HMetis {
+    /// Creates a new hMETIS partitioner.
+    ///
+    /// hMETIS offers no way of setting a seed, the given one is only recorded.
+    pub fn new(
+        path: PathBuf,
+        variant: Variant,
+        blocks: usize,
+        epsilon: f64,
+        seed: u64,
+        options: Options,
+    ) -> Self {
+        Self {
+            path,
+            variant,
+            blocks,
+            epsilon,
+            seed,
+            options,
+        }
+    }
+
+    /// Calculates the `UBfactor` corresponding to the epsilon of this partitioner.
+    ///
+    /// For direct k-way partitioning, it is the allowed imbalance in percent. For recursive
+    /// bisection, it applies to each bisection and is chosen such that the imbalance accumulated
+    /// over all levels stays within epsilon. hMETIS requires it to be at least `1`.
+    fn ubfactor(&self) -> u32 {
+        if let Some(ubfactor) = self.options.ubfactor {
+            return ubfactor;
+        }
+
+        let ubfactor = match self.variant {
+            Variant::Khmetis => self.epsilon * 100.0,
+            Variant::Shmetis | Variant::Hmetis => {
+                let levels = (self.blocks as f64).log2().ceil().max(1.0);
+                ((1.0 + self.epsilon).powf(1.0 / levels) / 2.0 - 0.5) * 100.0
+            }
+        };
+
+        (ubfactor.floor() as u32).max(1)
+    }
+}
+
+impl Partitioner for HMetis {
+    fn name_full(&self) -> String {
+        match self.variant {
+            Variant::Shmetis => format!("{} (UBfactor: {})", self.variant.name(), self.ubfactor()),
+            Variant::Hmetis => format!(
+                "{} (UBfactor: {}, runs: {}, CType: {}, RType: {})",
+                self.variant.name(),
+                self.ubfactor(),
+                self.options.runs,
+                self.options.coarsening,
+                self.options.refinement
+            ),
+            Variant::Khmetis => format!(
+                "{} (UBfactor: {}, runs: {}, CType: {}, metric: {})",
+                self.variant.name(),
+                self.ubfactor(),
+                self.options.runs,
+                self.options.coarsening,
+                self.options.metric
+            ),
+        }
+    }
+
+    fn name_short(&self) -> &'static str {
+        self.variant.name()
+    }
+
+    fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn run(
+        &self,
+        graph: &Graph,
+        limits: Limits,
+    ) -> Result<(u128, Usage, Partition), PartitionerError> {
+        self.run_external(graph, limits)
+    }
+}
+
+impl ExternalPartitioner for HMetis {
+    fn serialize_graph(&self, graph: &Graph) -> String {
+        let mut graph = graph.clone();
+        graph.trim();
+        graph.to_string_hmetis()
+    }
+
+    fn create_run(&self, graph: &Path) -> Command {
+        let mut run = Command::new(&self.path);
+
+        run.args([
+            graph.to_str().unwrap(),
+            &self.blocks.to_string(),
+            &self.ubfactor().to_string(),
+        ]);
+
+        match self.variant {
+            Variant::Shmetis => {}
+            // Nruns CType RType Vcycle Reconst dbglvl
+            Variant::Hmetis => {
+                run.args([
+                    &self.options.runs.to_string(),
+                    self.options.coarsening.as_arg(),
+                    self.options.refinement.as_arg(),
+                    "1",
+                    "0",
+                    "0",
+                ]);
+            }
+            // Nruns CType OType Vcycle dbglvl
+            Variant::Khmetis => {
+                run.args([
+                    &self.options.runs.to_string(),
+                    self.options.coarsening.as_arg(),
+                    self.options.metric.as_arg(),
+                    "1",
+                    "0",
+                ]);
+            }
+        }
+
+        run
+    }
+
+    fn output_file(&self, input: &Path) -> PathBuf {
+        let filename = input
+            .file_name()
+            .expect("Failed to resolve input file name.")
+            .to_str()
+            .expect("Failed to convert input file name.");
+
+        input
+            .parent()
+            .expect("Failed to resolve directory.")
+            .join(format!("{filename}.part.{}", self.blocks))
+    }
+}
@@ -1,5 +1,7 @@
-use super::Partitioner;
+use super::{ExternalPartitioner, Partitioner, PartitionerError};
+use crate::process::{Limits, Usage};
 use clap::ValueEnum;
+use hypergraph::Partition;
 use hypergraph_formats::hmetis::ToStringHMETIS;
 use hypergraph_formats::Graph;
 use std::io::Write;
@@ -12,7 +14,8 @@ pub struct MtKaHyPar {
     path: PathBuf,
     blocks: usize,
     metric: Metric,
-    epsilon: String,
+    epsilon: f64,
+    seed: u64,
     preset: Preset,
     preset_file: TempPath,
 }
@@ -40,7 +43,7 @@ impl Preset {
 }
 
 impl MtKaHyPar {
-    pub fn new(path: PathBuf, blocks: usize, metric: Metric, epsilon: String) -> Self {
+    pub fn new(path: PathBuf, blocks: usize, metric: Metric, epsilon: f64, seed: u64) -> Self {
         let preset = Preset::Default;
 
         // Write the preset to a temporary file.
@@ -54,6 +57,7 @@ impl MtKaHyPar {
             blocks,
             metric,
             epsilon,
+            seed,
             preset,
             preset_file: preset_file.into_temp_path(),
         }
@@ -76,6 +80,24 @@ impl Partitioner for MtKaHyPar {
         self.blocks
     }
 
+    fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn run(
+        &self,
+        graph: &Graph,
+        limits: Limits,
+    ) -> Result<(u128, Usage, Partition), PartitionerError> {
+        self.run_external(graph, limits)
+    }
+}
+
+impl ExternalPartitioner for MtKaHyPar {
     fn serialize_graph(&self, graph: &Graph) -> String {
         graph.to_string_hmetis()
     }
@@ -91,7 +113,9 @@ impl Partitioner for MtKaHyPar {
             "--objective",
             &self.metric.to_string(),
             "--epsilon",
-            &self.epsilon.clone(),
+            &self.epsilon.to_string(),
+            "--seed",
+            &self.seed.to_string(),
             "--preset",
             self.preset_file.to_str().unwrap(),
             "--write-partition-file",
@@ -112,8 +136,8 @@ impl Partitioner for MtKaHyPar {
             .parent()
             .expect("Failed to resolve directory.")
             .join(format!(
-                "{filename}.part{}.epsilon{}.seed0.KaHyPar",
-                self.blocks, self.epsilon
+                "{filename}.part{}.epsilon{}.seed{}.KaHyPar",
+                self.blocks, self.epsilon, self.seed
             ))
     }
 }
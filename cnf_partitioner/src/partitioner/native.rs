@@ -0,0 +1,120 @@
+use super::{Partitioner, PartitionerError};
+use crate::process::{self, Limits, Usage};
+use clap::ValueEnum;
+use hypergraph::{Hypergraph, Partition};
+use hypergraph_formats::Graph;
+use log::{debug, info};
+use strum::Display;
+
+/// A partitioner calling the algorithms of the `hypergraph` crate in-process.
+pub struct Native {
+    algorithm: Algorithm,
+    blocks: usize,
+    epsilon: f64,
+    seed: u64,
+}
+
+/// The partitioning algorithms available in the `hypergraph` crate.
+#[derive(Debug, Display, Copy, Clone, PartialEq, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Algorithm {
+    /// Assigns vertices to blocks in breadth-first search order.
+    Bfs,
+    /// Assigns vertices to blocks in depth-first search order.
+    Dfs,
+    /// Assigns vertices to random blocks.
+    Random,
+    /// Assigns clauses to blocks along a vertex separator of the variables, ignoring the dual
+    /// graph otherwise partitioned.
+    Separator,
+}
+
+impl Algorithm {
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Bfs => "BFS",
+            Algorithm::Dfs => "DFS",
+            Algorithm::Random => "Random",
+            Algorithm::Separator => "Separator",
+        }
+    }
+}
+
+impl Native {
+    /// Creates a new native partitioner.
+    ///
+    /// Only the random algorithm makes use of epsilon and the seed, the others only record them.
+    pub fn new(algorithm: Algorithm, blocks: usize, epsilon: f64, seed: u64) -> Self {
+        Self {
+            algorithm,
+            blocks,
+            epsilon,
+            seed,
+        }
+    }
+}
+
+impl Partitioner for Native {
+    fn name_full(&self) -> String {
+        match self.algorithm {
+            Algorithm::Random => format!("{} (seed: {})", self.algorithm.name(), self.seed),
+            Algorithm::Bfs | Algorithm::Dfs | Algorithm::Separator => {
+                self.algorithm.name().to_string()
+            }
+        }
+    }
+
+    fn name_short(&self) -> &'static str {
+        self.algorithm.name()
+    }
+
+    fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Runs the partitioning algorithm on the current thread.
+    ///
+    /// Only the partitioning itself is timed, not the conversion of the graph. As the algorithm is
+    /// not run in a separate process, the limits can not be enforced.
+    fn run(
+        &self,
+        graph: &Graph,
+        limits: Limits,
+    ) -> Result<(u128, Usage, Partition), PartitionerError> {
+        if limits.time.is_some() || limits.memory.is_some() {
+            debug!(
+                "{} does not support limits, ignoring them.",
+                self.name_full()
+            );
+        }
+
+        // The separator is computed on the primal graph, its vertices being the variables.
+        let hypergraph = match self.algorithm {
+            Algorithm::Separator => Hypergraph::from(&graph.dual()),
+            _ => Hypergraph::from(graph),
+        };
+
+        info!("Running {} ...", self.name_full());
+
+        let (time, usage, partition) = process::measure(|| match self.algorithm {
+            Algorithm::Bfs => hypergraph.partition_bfs(self.blocks),
+            Algorithm::Dfs => hypergraph.partition_dfs(self.blocks),
+            Algorithm::Random => {
+                hypergraph.partition_random(self.blocks, self.epsilon as f32, self.seed)
+            }
+            Algorithm::Separator => hypergraph.partition_separator(self.blocks),
+        });
+
+        info!("{} took {} ms.", self.name_full(), time);
+
+        Ok((time, usage, partition))
+    }
+}
@@ -1,5 +1,7 @@
-use super::Partitioner;
+use super::{ExternalPartitioner, Partitioner, PartitionerError};
+use crate::process::{Limits, Usage};
 use clap::ValueEnum;
+use hypergraph::Partition;
 use hypergraph_formats::patoh::ToStringPATOH;
 use hypergraph_formats::Graph;
 use std::path::{Path, PathBuf};
@@ -11,6 +13,8 @@ pub struct PaToH {
     blocks: usize,
     metric: Metric,
     preset: Preset,
+    epsilon: f64,
+    seed: u64,
 }
 
 #[derive(Debug, Copy, Clone, Display, ValueEnum)]
@@ -48,12 +52,21 @@ impl Preset {
 }
 
 impl PaToH {
-    pub fn new(path: PathBuf, blocks: usize, metric: Metric, preset: Preset) -> Self {
+    pub fn new(
+        path: PathBuf,
+        blocks: usize,
+        metric: Metric,
+        preset: Preset,
+        epsilon: f64,
+        seed: u64,
+    ) -> Self {
         Self {
             path,
             blocks,
             metric,
             preset,
+            epsilon,
+            seed,
         }
     }
 }
@@ -74,6 +87,24 @@ impl Partitioner for PaToH {
         self.blocks
     }
 
+    fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    fn run(
+        &self,
+        graph: &Graph,
+        limits: Limits,
+    ) -> Result<(u128, Usage, Partition), PartitionerError> {
+        self.run_external(graph, limits)
+    }
+}
+
+impl ExternalPartitioner for PaToH {
     fn serialize_graph(&self, graph: &Graph) -> String {
         graph.to_string_patoh()
     }
@@ -86,6 +117,8 @@ impl Partitioner for PaToH {
             &self.blocks.to_string(),
             format!("UM={}", &self.metric.as_arg()).as_str(),
             format!("PQ={}", &self.preset.as_arg()).as_str(),
+            format!("IB={}", self.epsilon).as_str(),
+            format!("SD={}", self.seed).as_str(),
         ]);
 
         run
@@ -0,0 +1,712 @@
+use crate::assignment::find_assignments;
+use crate::batch::Deadline;
+use crate::cnf::{
+    count_free_variables, serialize_cut, serialize_variable_map, serialize_variable_order,
+    variable_order,
+};
+use crate::components::{components, largest, Decomposition};
+use crate::compose::compose;
+use crate::cubes::count_cubes;
+use crate::d4::{Compilation, D4};
+use crate::dtree::bisect;
+use crate::output::{Output, Run};
+use crate::partitioner::{Partitioner, PartitionerError};
+use crate::preprocess::{preprocess, Method, Preprocessing, Technique};
+use crate::process::{self, Limits};
+use crate::recursive::Recursion;
+use crate::refine::{refine, Refinement};
+use crate::selection::Selection;
+use crate::Args;
+use clap::ValueEnum;
+use ddnnf::Nnf;
+use hypergraph_formats::cnf::{cut_variables, Cnf, CnfError, Literal, VariableHeuristic};
+use hypergraph_formats::Graph;
+use log::{info, warn};
+use num::BigInt;
+use std::ffi::OsString;
+use std::io::{Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+use tempfile::NamedTempFile;
+
+/// Runs all partitioners and heuristics on a single instance, adding each run to the output.
+pub fn run_instance(
+    args: &Args,
+    d4: &D4,
+    partitioners: &[Box<dyn Partitioner>],
+    input: &Path,
+    deadline: Deadline,
+    output: &mut Output,
+) -> io::Result<()> {
+    info!("Running on {}", input.display());
+
+    info!("Preprocessing the input using {}.", args.preprocessing);
+
+    let (cnf, preprocessing) = preprocess_input(args, d4, input, deadline)?;
+
+    // Save the CNF if requested.
+    if let Some(path) = &args.save_cnf {
+        fs::write(path, cnf.to_string())?;
+    };
+
+    info!("Running d4 on the original CNF.");
+
+    // Compile the CNF using d4.
+    let original = if args.skip_original {
+        Compilation::default()
+    } else {
+        let dump = args.dump_ddnnf.as_deref().map(|path| append(path, ".nnf"));
+
+        compile(
+            d4,
+            &cnf,
+            dump.as_deref(),
+            args.timeout,
+            deadline,
+            args.verify_ddnnf,
+        )?
+    };
+
+    // Decompose the CNF into independent components.
+    let components = components(&cnf);
+    info!("Found {} component(s).", components.len());
+
+    let decomposition = count_components(args, d4, &cnf, &components, &original, deadline)?;
+
+    // Continue with the largest component only if requested.
+    let cnf = match largest(&components) {
+        Some(index) if args.largest_component => {
+            info!(
+                "Partitioning only the largest component with {} clauses.",
+                components[index].len()
+            );
+            components[index].clone()
+        }
+        _ => cnf,
+    };
+
+    // We will do at least a single pass per partitioner using no heuristic but applying unit weights
+    // for each variable.
+    let mut heuristics = vec![VariableHeuristic::None];
+    heuristics.append(&mut args.heuristics.clone());
+
+    // Grow the amount of blocks per configuration until it stops improving if requested.
+    let mut selection = args
+        .auto_blocks
+        .map(|factor| Selection::new(args.auto_blocks_criterion, factor));
+
+    let instance = input
+        .file_stem()
+        .expect("Failed to extract input file stem.")
+        .to_str()
+        .expect("Failed to convert input file name to string.")
+        .to_string();
+
+    // Do a run per partitioner ...
+    for partitioner in partitioners {
+        // ... and per heuristic.
+        for &heuristic in &heuristics {
+            deadline.check()?;
+
+            if selection
+                .as_ref()
+                .is_some_and(|selection| selection.stopped(partitioner.as_ref(), heuristic))
+            {
+                info!(
+                    "Skipping {} as fewer blocks were not improved on.",
+                    partitioner.name_full()
+                );
+                continue;
+            }
+
+            info!("Using the {} heuristic.", heuristic);
+
+            // Transform the CNF into a dual hypergraph.
+            let graph = Graph::from((&cnf, heuristic)).dual();
+
+            // Generate the partition.
+            let limits = Limits::new(deadline.limit(args.partitioner_timeout)?, args.memory_limit);
+            let (partitioning_time, partitioning_usage, mut partition) =
+                match partitioner.run(&graph, limits) {
+                    Ok(result) => result,
+                    // Only this partitioner is stopped, so record the timeout and go on.
+                    Err(PartitionerError::Timeout) => {
+                        let mut run = Run::new(
+                            instance.clone(),
+                            partitioner.name_short(),
+                            heuristic,
+                            partitioner.blocks(),
+                            partitioner.epsilon(),
+                            0.0,
+                            partitioner.seed(),
+                            0,
+                            original.clone(),
+                            Compilation::default(),
+                            u128::MAX,
+                            Default::default(),
+                        );
+
+                        run.set_preprocessing(preprocessing.clone());
+                        run.set_decomposition(decomposition.clone());
+                        output.add(run);
+                        continue;
+                    }
+                    Err(error) => return Err(error.into()),
+                };
+
+            // Reject partitions of external tools assigning blocks outside the requested ones.
+            cnf.check_partition(partition.as_slice(), partitioner.blocks())
+                .map_err(invalid_data)?;
+
+            // Move clauses between blocks to reduce the cut if requested.
+            let refinement = if args.refine {
+                let epsilon = args.refine_epsilon.unwrap_or(partitioner.epsilon());
+                let refinement = refine(&cnf, &mut partition, partitioner.blocks(), epsilon);
+
+                info!(
+                    "Refinement moved {} clause(s), reducing the cut from {} to {}.",
+                    refinement.moves, refinement.cut_before, refinement.cut_after
+                );
+
+                Some(refinement)
+            } else {
+                None
+            };
+
+            // Save the partition if requested.
+            if let Some(path) = &args.save_partitions {
+                fs::write(
+                    run_path(path, partitioner.as_ref(), heuristic),
+                    partition.to_string(),
+                )?;
+            };
+
+            // Split the original CNF into the respective CNFs as defined by the partition.
+            let split = cnf
+                .split(partition.as_slice(), partitioner.blocks())
+                .map_err(invalid_data)?;
+
+            if !split.empty_blocks.is_empty() {
+                warn!(
+                    "{} left blocks {:?} empty.",
+                    partitioner.name_full(),
+                    split.empty_blocks
+                );
+            }
+
+            let parts = split.parts;
+
+            // Calculate the cut set.
+            let cut = cut_variables(&parts);
+            info!("{} cut size: {}", partitioner.name_full(), cut.len());
+
+            // Save the variable order derived from the split if requested.
+            if let Some(path) = &args.save_order {
+                fs::write(
+                    append(run_path(path, partitioner.as_ref(), heuristic), ".order"),
+                    serialize_variable_order(&variable_order(&cnf, &parts)),
+                )?;
+            }
+
+            // Without refinement, the cut stays the same.
+            let refinement = refinement.unwrap_or(Refinement {
+                cut_before: cut.len(),
+                cut_after: cut.len(),
+                moves: 0,
+            });
+
+            // Build a dtree of the clauses by recursive bisection if requested.
+            let bisection = if args.dtree {
+                info!("Building a dtree by recursive bisection.");
+
+                let (dtree, bisection) = bisect(args, &graph, partitioner.as_ref(), deadline)?;
+                info!(
+                    "{} dtree width: {}, largest cutset: {}",
+                    partitioner.name_full(),
+                    bisection.width,
+                    bisection.max_cutset
+                );
+
+                // Save it if requested.
+                if let Some(path) = &args.save_dtree {
+                    fs::write(
+                        append(run_path(path, partitioner.as_ref(), heuristic), ".dtree"),
+                        dtree.to_string(),
+                    )?;
+                }
+
+                bisection
+            } else {
+                Default::default()
+            };
+
+            // Find the assignments that split the CNFs, there being none for an unsatisfiable CNF.
+            let mut assignments = find_assignments(
+                &cnf,
+                &cut,
+                args.assignment_strategy,
+                args.assignment_samples,
+                partitioner.seed(),
+            );
+
+            // The first assignment is the one conditioned on, further samples are only timed.
+            let samples = assignments.split_off(assignments.len().min(1));
+            let assignment = assignments.pop();
+
+            // Save the cut set and its assignment if requested.
+            if let Some(path) = &args.save_cut {
+                fs::write(
+                    append(run_path(path, partitioner.as_ref(), heuristic), ".cut"),
+                    serialize_cut(&cnf, &cut, assignment.as_deref()),
+                )?;
+            }
+
+            // Run d4 on the original instance with the assignment.
+            let conditioned = match &assignment {
+                Some(assignment) => {
+                    let conditioned_cnf = cnf.condition(assignment).map_err(invalid_data)?;
+
+                    info!("Running d4 on the original CNF with the split assignment.");
+
+                    // Compile the CNF using d4.
+                    let dump = args.dump_ddnnf.as_deref().map(|path| {
+                        append(
+                            run_path(path, partitioner.as_ref(), heuristic),
+                            ".conditioned.nnf",
+                        )
+                    });
+
+                    compile(
+                        d4,
+                        &conditioned_cnf,
+                        dump.as_deref(),
+                        None,
+                        deadline,
+                        args.verify_ddnnf,
+                    )?
+                }
+                None => Compilation::default(),
+            };
+
+            let mut run = Run::new(
+                instance.clone(),
+                partitioner.name_short(),
+                heuristic,
+                partitioner.blocks(),
+                partitioner.epsilon(),
+                partition.imbalance(partitioner.blocks()),
+                partitioner.seed(),
+                cut.len(),
+                original.clone(),
+                conditioned,
+                partitioning_time,
+                partitioning_usage,
+            );
+
+            run.set_empty_blocks(split.empty_blocks.len());
+            run.set_refinement(refinement);
+            run.set_bisection(bisection);
+            run.set_preprocessing(preprocessing.clone());
+            run.set_decomposition(decomposition.clone());
+            run.set_assignment(
+                args.assignment_strategy,
+                assignment.clone().unwrap_or_default(),
+            );
+
+            // Count the satisfying cut assignments to estimate the cost of all of them if requested.
+            if let Some(limit) = args.count_cubes {
+                info!("Counting the satisfying cut assignments.");
+
+                let cubes = count_cubes(&cnf, &cut, limit, args.cube_probes, partitioner.seed());
+
+                info!(
+                    "{} satisfying cut assignments: {}{}",
+                    partitioner.name_full(),
+                    cubes.count,
+                    if cubes.exact { "" } else { " (estimated)" }
+                );
+
+                run.set_cubes(cubes);
+            }
+
+            // Without an assignment there is nothing to split.
+            let Some(assignment) = assignment else {
+                warn!("The CNF is unsatisfiable, skipping the split CNFs.");
+
+                run.set_unsatisfiable();
+
+                if let Some(selection) = &mut selection {
+                    let value = run.criterion(selection.criterion());
+                    selection.add(partitioner.as_ref(), heuristic, value);
+                }
+
+                output.add(run);
+                continue;
+            };
+
+            // Condition the CNFs on the assignment.
+            let cnfs = parts
+                .iter()
+                .map(|cnf| cnf.condition(&assignment))
+                .collect::<Result<Vec<Cnf>, CnfError>>()
+                .map_err(invalid_data)?;
+
+            // Variables not occurring in any CNF are dropped by compacting them.
+            run.add_free_variables(count_free_variables(&cnf, &cnfs));
+
+            info!("Running d4 on each split CNF.");
+
+            // Solve each split CNF.
+            for (index, cnf) in cnfs.iter().enumerate() {
+                // Renumber its variables densely.
+                let (cnf, variables) = cnf.compact();
+
+                // Save it and its variable map if requested.
+                if let Some(path) = &args.save_split {
+                    let path = run_path(path, partitioner.as_ref(), heuristic);
+
+                    let mut cnf_path = path.clone();
+                    cnf_path.push(format!(".{index}.cnf"));
+                    fs::write(cnf_path, cnf.to_string())?;
+
+                    let mut map_path = path;
+                    map_path.push(format!(".{index}.map"));
+                    fs::write(map_path, serialize_variable_map(&variables))?;
+                }
+
+                // Compile the CNF using d4.
+                let dump = args.dump_ddnnf.as_deref().map(|path| {
+                    append(
+                        run_path(path, partitioner.as_ref(), heuristic),
+                        &format!(".{index}.nnf"),
+                    )
+                });
+
+                run.add_part(compile(
+                    d4,
+                    &cnf,
+                    dump.as_deref(),
+                    args.timeout,
+                    deadline,
+                    args.verify_ddnnf,
+                )?);
+            }
+
+            // Time the split CNFs for each further sampled assignment.
+            if !samples.is_empty() {
+                info!(
+                    "Running d4 on each split CNF for {} further assignment(s).",
+                    samples.len()
+                );
+
+                for sample in &samples {
+                    run.add_sample(split_time(args, d4, &parts, sample, deadline)?);
+                }
+            }
+
+            // Count the parts again by splitting them recursively if requested.
+            if args.recursive {
+                info!("Counting the split CNFs recursively.");
+
+                let recursion = Recursion {
+                    args,
+                    d4,
+                    partitioner: partitioner.as_ref(),
+                    heuristic,
+                    deadline,
+                };
+
+                let trace = recursion.root(&cnf, &cnfs, cut.len(), partitioning_time)?;
+
+                // Save it if requested.
+                if let Some(path) = &args.save_trace {
+                    let json = serde_json::to_string_pretty(&trace).map_err(Error::other)?;
+
+                    fs::write(
+                        append(run_path(path, partitioner.as_ref(), heuristic), ".json"),
+                        json,
+                    )?;
+                }
+
+                run.set_recursion(trace);
+            }
+
+            // Compose a d-DNNF of the whole CNF if requested and feasible.
+            if args.compose.is_some_and(|max| cut.len() <= max) {
+                info!("Composing a d-DNNF from the parts per cut assignment.");
+
+                let (nnf, composition) = compose(d4, &cnf, &parts, &cut, args.timeout, deadline)?;
+
+                if let (Some(nnf), Some(path)) = (&nnf, &args.dump_ddnnf) {
+                    fs::write(
+                        append(
+                            run_path(path, partitioner.as_ref(), heuristic),
+                            ".composed.nnf",
+                        ),
+                        nnf.to_string(),
+                    )?;
+                }
+
+                if let Some(nnf) = nnf.filter(|_| args.verify_ddnnf) {
+                    nnf.validate()
+                        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+                }
+
+                if !composition.timed_out()
+                    && !args.skip_original
+                    && !original.timed_out()
+                    && original.count != composition.count
+                {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "the original CNF has {} models but the composed d-DNNF has {}",
+                            original.count, composition.count
+                        ),
+                    ));
+                }
+
+                run.set_composition(composition);
+            }
+
+            run.check();
+
+            if let Some(selection) = &mut selection {
+                let value = run.criterion(selection.criterion());
+                selection.add(partitioner.as_ref(), heuristic, value);
+            }
+
+            output.add(run);
+        }
+    }
+
+    // Record the trajectories in the runs they consist of.
+    if let Some(selection) = &selection {
+        output.select_blocks(selection);
+    }
+
+    Ok(())
+}
+
+/// Preprocesses an input using the configured method, returning the resulting CNF.
+fn preprocess_input(
+    args: &Args,
+    d4: &D4,
+    input: &Path,
+    deadline: Deadline,
+) -> io::Result<(Cnf, Preprocessing)> {
+    let mut preprocessing = Preprocessing {
+        method: args.preprocessing,
+        ..Default::default()
+    };
+
+    let cnf = match args.preprocessing {
+        Method::D4 => {
+            // Create a temporary file for the preprocessed CNF.
+            let preprocessed = NamedTempFile::new()?.into_temp_path();
+
+            // Preprocess the CNF using d4.
+            preprocessing.time =
+                d4.preprocess(input.to_path_buf(), &preprocessed, deadline.limit(None)?)?;
+
+            let cnf = read_cnf(&preprocessed)?;
+
+            // d4 drops comments, so the names are taken from the input if its variables are kept.
+            let original = read_cnf(input)?;
+
+            if cnf.names().is_empty() && cnf.num_vars() == original.num_vars() {
+                cnf.with_names(original.names().clone())
+            } else {
+                cnf
+            }
+        }
+        Method::Native => {
+            let cnf = read_cnf(input)?;
+
+            // Apply all techniques not switched off.
+            let techniques: Vec<Technique> = Technique::value_variants()
+                .iter()
+                .filter(|technique| !args.preprocessing_disable.contains(technique))
+                .copied()
+                .collect();
+
+            let (time, _, (cnf, factor)) = process::measure(|| preprocess(&cnf, &techniques));
+            preprocessing.time = time;
+            preprocessing.factor = factor;
+
+            cnf
+        }
+        Method::None => read_cnf(input)?,
+    };
+
+    Ok((cnf, preprocessing))
+}
+
+/// Compiles the parts of a split CNF conditioned on an assignment, returning the total time taken.
+///
+/// The time is `u128::MAX` in case compiling any part reached the time limit.
+fn split_time(
+    args: &Args,
+    d4: &D4,
+    parts: &[Cnf],
+    assignment: &[Literal],
+    deadline: Deadline,
+) -> io::Result<u128> {
+    let mut time = 0u128;
+
+    for part in parts {
+        let (part, _) = part.condition(assignment).map_err(invalid_data)?.compact();
+        let compilation = compile(d4, &part, None, args.timeout, deadline, false)?;
+
+        time = time.saturating_add(compilation.time);
+    }
+
+    Ok(time)
+}
+
+/// Counts the models of the CNF by counting each of its components separately.
+///
+/// A CNF of at most one component is not counted again but takes the count of the original one.
+fn count_components(
+    args: &Args,
+    d4: &D4,
+    cnf: &Cnf,
+    components: &[Cnf],
+    original: &Compilation,
+    deadline: Deadline,
+) -> io::Result<Decomposition> {
+    let mut decomposition = Decomposition {
+        sizes: components.iter().map(Cnf::len).collect(),
+        count: original.count.clone(),
+        time: 0,
+    };
+
+    if components.len() < 2 {
+        return Ok(decomposition);
+    }
+
+    info!("Running d4 on each component.");
+
+    let mut finished = true;
+    decomposition.count = BigInt::from(1);
+
+    for component in components {
+        // Only the variables of the component are relevant for its count.
+        let (component, _) = component.compact();
+
+        let compilation = compile(d4, &component, None, args.timeout, deadline, false)?;
+
+        finished &= !compilation.timed_out();
+        decomposition.count *= compilation.count;
+        decomposition.time = decomposition.time.saturating_add(compilation.time);
+    }
+
+    // Variables occurring in no component double the count.
+    decomposition.count <<= count_free_variables(cnf, components);
+
+    if finished
+        && !args.skip_original
+        && !original.timed_out()
+        && original.count != decomposition.count
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "the original CNF has {} models but its components (collectively) have {}",
+                original.count, decomposition.count
+            ),
+        ));
+    }
+
+    Ok(decomposition)
+}
+
+/// Reads and parses a CNF in the DIMACS format.
+fn read_cnf(path: &Path) -> io::Result<Cnf> {
+    let content = fs::read_to_string(path).map_err(|error| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Failed to read CNF file {:?}: {}", path, error),
+        )
+    })?;
+
+    content.parse().map_err(invalid_data)
+}
+
+/// Wraps a CNF error into an I/O error of invalid data.
+pub(crate) fn invalid_data(error: CnfError) -> Error {
+    Error::new(ErrorKind::InvalidData, error)
+}
+
+/// Appends the partitioner, the heuristic, the blocks, the epsilon and the seed of a run to a path.
+fn run_path(path: &Path, partitioner: &dyn Partitioner, heuristic: VariableHeuristic) -> OsString {
+    let mut path = path.as_os_str().to_owned();
+
+    for part in [
+        partitioner.name_short().to_string(),
+        heuristic.to_string(),
+        partitioner.blocks().to_string(),
+        partitioner.epsilon().to_string(),
+        partitioner.seed().to_string(),
+    ] {
+        path.push(".");
+        path.push(part);
+    }
+
+    path
+}
+
+/// Appends a suffix to a path.
+fn append(path: impl Into<OsString>, suffix: &str) -> PathBuf {
+    let mut path = path.into();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Compiles a CNF using d4 while respecting the per-instance deadline.
+///
+/// The d-DNNF is dumped to the given path if any and verified if requested.
+/// Returns an error in case the deadline is reached before or while compiling.
+pub fn compile(
+    d4: &D4,
+    cnf: &Cnf,
+    dump: Option<&Path>,
+    timeout: Option<u64>,
+    deadline: Deadline,
+    verify: bool,
+) -> io::Result<Compilation> {
+    // Write the CNF to a temporary file.
+    let mut file = NamedTempFile::new()?;
+    file.write_all(cnf.to_string().as_bytes())?;
+    let path = file.into_temp_path();
+
+    let result = d4.compile(path.to_path_buf(), deadline.limit(timeout)?, dump)?;
+
+    // A timeout of d4 might have been caused by the instance deadline.
+    deadline.check()?;
+
+    match dump {
+        Some(dump) if verify && !result.timed_out() => verify_ddnnf(dump, cnf, &result.count)?,
+        _ => {}
+    }
+
+    Ok(result)
+}
+
+/// Checks that a dumped d-DNNF is valid and has the model count reported by d4.
+fn verify_ddnnf(path: &Path, cnf: &Cnf, count: &BigInt) -> io::Result<()> {
+    let nnf = Nnf::read(path).map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+    nnf.validate()
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+
+    let counted = nnf.count(cnf.num_vars());
+
+    if &counted != count {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("d4 reported {count} models but its d-DNNF has {counted}"),
+        ));
+    }
+
+    Ok(())
+}
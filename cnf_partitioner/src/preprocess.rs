@@ -0,0 +1,689 @@
+use clap::ValueEnum;
+use hypergraph_formats::cnf::{Clause, Cnf, Literal};
+use log::info;
+use num::BigInt;
+use std::collections::BTreeMap;
+use strum::Display;
+
+/// The largest amount of variables of a component to count by enumerating all its assignments.
+const SMALL_COMPONENT: usize = 16;
+
+/// How to preprocess an input before partitioning it.
+#[derive(Debug, Display, Copy, Clone, Default, PartialEq, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Method {
+    /// Runs d4 with `--only-preproc`.
+    #[default]
+    D4,
+    /// Applies the native preprocessing techniques.
+    Native,
+    /// Uses the input as is.
+    None,
+}
+
+/// The techniques of the native preprocessing, each preserving the model count up to a factor.
+#[derive(Debug, Display, Copy, Clone, PartialEq, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Technique {
+    /// Assigns the literals of unit clauses and simplifies all clauses accordingly.
+    Units,
+    /// Drops the free variables occurring in no clause, each doubling the count.
+    /// Pure literals are deliberately not eliminated as that would change the count.
+    Free,
+    /// Removes clauses containing all literals of another clause.
+    Subsumption,
+    /// Substitutes literals equivalent by binary clauses with a representative literal.
+    Equivalences,
+    /// Counts the models of small independent components directly, keeping the largest one.
+    Components,
+}
+
+/// How an input was preprocessed.
+#[derive(Debug, Clone)]
+pub struct Preprocessing {
+    /// The method used.
+    pub method: Method,
+    /// The wall-clock time taken in ms.
+    pub time: u128,
+    /// The model count of the input divided by the one of the preprocessed CNF.
+    pub factor: BigInt,
+}
+
+impl Default for Preprocessing {
+    fn default() -> Self {
+        Self {
+            method: Method::default(),
+            time: 0,
+            factor: BigInt::from(1),
+        }
+    }
+}
+
+/// Preprocesses a CNF using the given techniques.
+///
+/// Returns the preprocessed CNF over densely renumbered variables and the factor to multiply its
+/// model count with for obtaining the one of the input.
+pub fn preprocess(cnf: &Cnf, techniques: &[Technique]) -> (Cnf, BigInt) {
+    let mut state = State::new(cnf);
+
+    // Simplify until no technique changes the clauses anymore.
+    loop {
+        let mut changed = false;
+
+        if techniques.contains(&Technique::Units) {
+            changed |= state.propagate_units();
+        }
+
+        if techniques.contains(&Technique::Equivalences) {
+            changed |= state.substitute_equivalences();
+        }
+
+        if techniques.contains(&Technique::Subsumption) {
+            changed |= state.remove_subsumed();
+        }
+
+        if !changed || state.unsatisfiable {
+            break;
+        }
+    }
+
+    if techniques.contains(&Technique::Components) {
+        state.count_small_components();
+    }
+
+    let (preprocessed, factor) = state.finish(techniques.contains(&Technique::Free));
+
+    info!(
+        "Native preprocessing left {} of {} variables and {} of {} clauses, factor: {}",
+        preprocessed.num_vars(),
+        cnf.num_vars(),
+        preprocessed.len(),
+        cnf.len(),
+        factor
+    );
+
+    (preprocessed, factor)
+}
+
+/// The intermediate state of the native preprocessing.
+struct State {
+    num_vars: u64,
+    clauses: Vec<Vec<Literal>>,
+    /// Whether each variable was removed by determining its value, indexed by the variable minus one.
+    eliminated: Vec<bool>,
+    factor: BigInt,
+    unsatisfiable: bool,
+    /// The names of the variables, kept for those remaining.
+    names: BTreeMap<u64, String>,
+}
+
+impl State {
+    /// Creates the initial state, already dropping duplicate literals and tautologies.
+    fn new(cnf: &Cnf) -> Self {
+        let mut state = Self {
+            num_vars: cnf.num_vars(),
+            clauses: Vec::with_capacity(cnf.len()),
+            eliminated: vec![false; cnf.num_vars() as usize],
+            factor: BigInt::from(1),
+            unsatisfiable: false,
+            names: cnf.names().clone(),
+        };
+
+        for clause in cnf.clauses() {
+            state.push(clause.literals().copied().collect());
+        }
+
+        state
+    }
+
+    /// Adds a clause after sorting it, unless it is a tautology.
+    fn push(&mut self, mut clause: Vec<Literal>) {
+        clause.sort();
+        clause.dedup();
+
+        if clause
+            .iter()
+            .any(|&literal| clause.binary_search(&!literal).is_ok())
+        {
+            return;
+        }
+
+        if clause.is_empty() {
+            self.unsatisfiable = true;
+        }
+
+        self.clauses.push(clause);
+    }
+
+    /// Assigns all unit clauses until none is left, returning whether any was found.
+    fn propagate_units(&mut self) -> bool {
+        let mut assignment: Vec<Option<bool>> = vec![None; self.num_vars as usize];
+        let mut changed = false;
+
+        while !self.unsatisfiable {
+            // Collect all current units.
+            let mut units = false;
+
+            for clause in self.clauses.iter().filter(|clause| clause.len() == 1) {
+                let literal = clause[0];
+
+                match &mut assignment[index(literal.variable())] {
+                    value @ None => {
+                        *value = Some(literal.is_positive());
+                        units = true;
+                    }
+                    Some(value) if *value != literal.is_positive() => self.unsatisfiable = true,
+                    Some(_) => {}
+                }
+            }
+
+            if !units || self.unsatisfiable {
+                break;
+            }
+
+            changed = true;
+
+            // Drop satisfied clauses and falsified literals.
+            let value = |literal: &Literal| {
+                assignment[index(literal.variable())].map(|value| value == literal.is_positive())
+            };
+
+            let clauses = std::mem::take(&mut self.clauses);
+
+            for clause in clauses {
+                if !clause.iter().any(|literal| value(literal) == Some(true)) {
+                    self.push(
+                        clause
+                            .into_iter()
+                            .filter(|literal| value(literal).is_none())
+                            .collect(),
+                    );
+                }
+            }
+        }
+
+        assignment
+            .iter()
+            .zip(&mut self.eliminated)
+            .filter(|(value, _)| value.is_some())
+            .for_each(|(_, eliminated)| *eliminated = true);
+
+        changed
+    }
+
+    /// Replaces each literal by the representative of the literals equivalent to it, returning
+    /// whether any was replaced.
+    ///
+    /// Equivalent literals form strongly connected components in the implication graph of the
+    /// binary clauses.
+    fn substitute_equivalences(&mut self) -> bool {
+        if self.unsatisfiable {
+            return false;
+        }
+
+        // Each binary clause `a b` adds the implications `-a -> b` and `-b -> a`.
+        let mut implications = vec![Vec::new(); 2 * self.num_vars as usize];
+
+        for clause in self.clauses.iter().filter(|clause| clause.len() == 2) {
+            implications[node(!clause[0])].push(node(clause[1]));
+            implications[node(!clause[1])].push(node(clause[0]));
+        }
+
+        let components = strongly_connected_components(&implications);
+
+        // The representative of a component is its literal of the smallest variable.
+        let mut representatives = vec![usize::MAX; implications.len()];
+
+        for (node, &component) in components.iter().enumerate() {
+            representatives[component] = representatives[component].min(node);
+        }
+
+        let mut changed = false;
+
+        for variable in 1..=self.num_vars {
+            let positive = node(Literal::from_variable(variable, true));
+
+            // A literal equivalent to its negation makes the CNF unsatisfiable.
+            if components[positive] == components[positive + 1] {
+                self.unsatisfiable = true;
+                return true;
+            }
+
+            if representatives[components[positive]] != positive {
+                self.eliminated[index(variable)] = true;
+                changed = true;
+            }
+        }
+
+        if changed {
+            let clauses = std::mem::take(&mut self.clauses);
+
+            for clause in clauses {
+                self.push(
+                    clause
+                        .into_iter()
+                        .map(|literal| literal_of(representatives[components[node(literal)]]))
+                        .collect(),
+                );
+            }
+        }
+
+        changed
+    }
+
+    /// Removes each clause containing all literals of another one, returning whether any was
+    /// removed.
+    fn remove_subsumed(&mut self) -> bool {
+        if self.unsatisfiable {
+            return false;
+        }
+
+        let mut occurrences = vec![Vec::new(); 2 * self.num_vars as usize];
+
+        for (index, clause) in self.clauses.iter().enumerate() {
+            clause
+                .iter()
+                .for_each(|&literal| occurrences[node(literal)].push(index));
+        }
+
+        // Shorter clauses are checked first as only they can subsume longer ones.
+        let mut order: Vec<usize> = (0..self.clauses.len()).collect();
+        order.sort_by_key(|&index| self.clauses[index].len());
+
+        let mut removed = vec![false; self.clauses.len()];
+
+        for index in order {
+            if removed[index] {
+                continue;
+            }
+
+            let clause = &self.clauses[index];
+
+            // Each subsumed clause contains the literal of this clause occurring least often.
+            let literal = clause
+                .iter()
+                .min_by_key(|&&literal| occurrences[node(literal)].len())
+                .expect("Clauses are not empty while satisfiable.");
+
+            for &other in &occurrences[node(*literal)] {
+                if other != index
+                    && !removed[other]
+                    && clause
+                        .iter()
+                        .all(|literal| self.clauses[other].binary_search(literal).is_ok())
+                {
+                    removed[other] = true;
+                }
+            }
+        }
+
+        let changed = removed.contains(&true);
+
+        if changed {
+            let mut removed = removed.into_iter();
+            self.clauses.retain(|_| !removed.next().unwrap());
+        }
+
+        changed
+    }
+
+    /// Counts the models of components having at most [`SMALL_COMPONENT`] variables by
+    /// enumeration and removes them, always keeping the component with the most variables.
+    fn count_small_components(&mut self) {
+        if self.unsatisfiable {
+            return;
+        }
+
+        // Connect the variables of each clause.
+        let mut parents: Vec<usize> = (0..self.num_vars as usize).collect();
+
+        for clause in &self.clauses {
+            let first = find(&mut parents, index(clause[0].variable()));
+
+            for literal in &clause[1..] {
+                let root = find(&mut parents, index(literal.variable()));
+                parents[root] = first;
+            }
+        }
+
+        // Group the clauses and variables by component.
+        let mut components: BTreeMap<usize, (Vec<usize>, Vec<u64>)> = BTreeMap::new();
+
+        for (index, clause) in self.clauses.iter().enumerate() {
+            let root = find(&mut parents, self::index(clause[0].variable()));
+            let (clauses, variables) = components.entry(root).or_default();
+
+            clauses.push(index);
+            variables.extend(clause.iter().map(|literal| literal.variable()));
+        }
+
+        if components.len() < 2 {
+            return;
+        }
+
+        components.values_mut().for_each(|(_, variables)| {
+            variables.sort();
+            variables.dedup();
+        });
+
+        let largest = *components
+            .iter()
+            .max_by_key(|(_, (_, variables))| variables.len())
+            .expect("There are at least two components.")
+            .0;
+
+        let mut removed = vec![false; self.clauses.len()];
+
+        for (root, (indices, variables)) in &components {
+            if *root == largest || variables.len() > SMALL_COMPONENT {
+                continue;
+            }
+
+            let clauses: Vec<&Vec<Literal>> =
+                indices.iter().map(|&index| &self.clauses[index]).collect();
+
+            let count = count_models(&clauses, variables);
+
+            if count == 0 {
+                self.unsatisfiable = true;
+                return;
+            }
+
+            self.factor *= count;
+
+            variables
+                .iter()
+                .for_each(|&variable| self.eliminated[index(variable)] = true);
+
+            indices.iter().for_each(|&index| removed[index] = true);
+        }
+
+        let mut removed = removed.into_iter();
+        self.clauses.retain(|_| !removed.next().unwrap());
+    }
+
+    /// Creates the preprocessed CNF, renumbering its variables densely.
+    ///
+    /// Variables neither occurring nor eliminated are free. They are dropped if `drop_free` is set,
+    /// each doubling the factor, or kept as the highest variables otherwise. Names are only kept
+    /// for the variables occurring.
+    fn finish(mut self, drop_free: bool) -> (Cnf, BigInt) {
+        // Represent an unsatisfiable CNF by a single contradiction.
+        if self.unsatisfiable {
+            let literal = Literal::from_variable(1, true);
+            let clauses = vec![Clause::new(vec![literal]), Clause::new(vec![!literal])];
+
+            return (
+                Cnf::new(1, clauses).expect("Contradiction should be valid."),
+                BigInt::from(1),
+            );
+        }
+
+        let cnf = Cnf::new(
+            self.num_vars,
+            self.clauses.into_iter().map(Clause::from).collect(),
+        )
+        .expect("Preprocessing should not introduce variables.")
+        .with_names(self.names);
+
+        let (mut compacted, variables) = cnf.compact();
+
+        let eliminated = self
+            .eliminated
+            .iter()
+            .filter(|&&eliminated| eliminated)
+            .count();
+        let free = self.num_vars as usize - variables.len() - eliminated;
+
+        // CNFs without clauses are rejected by DIMACS parsers and partitioners alike, so a single
+        // unit clause having one model as well stands in for them.
+        if compacted.is_empty() {
+            let clauses = vec![Clause::new(vec![Literal::from_variable(1, true)])];
+            compacted = Cnf::new(1, clauses).expect("Unit clause should be valid.");
+        }
+
+        if drop_free {
+            self.factor <<= free;
+            return (compacted, self.factor);
+        }
+
+        let cnf = Cnf::new(
+            compacted.num_vars() + free as u64,
+            compacted.clauses().to_vec(),
+        )
+        .expect("Adding free variables should be valid.")
+        .with_names(compacted.names().clone());
+
+        (cnf, self.factor)
+    }
+}
+
+/// The index of a variable in per-variable vectors.
+fn index(variable: u64) -> usize {
+    variable as usize - 1
+}
+
+/// The node of a literal in per-literal vectors, the positive one preceding the negative one.
+fn node(literal: Literal) -> usize {
+    2 * index(literal.variable()) + usize::from(!literal.is_positive())
+}
+
+/// The literal of a node as created by [`node`].
+fn literal_of(node: usize) -> Literal {
+    Literal::from_variable(node as u64 / 2 + 1, node.is_multiple_of(2))
+}
+
+/// Finds the root of an element in a union-find forest, compressing the path to it.
+fn find(parents: &mut [usize], element: usize) -> usize {
+    let mut root = element;
+
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    let mut current = element;
+
+    while parents[current] != root {
+        current = std::mem::replace(&mut parents[current], root);
+    }
+
+    root
+}
+
+/// Finds the strongly connected components of a directed graph using Tarjan's algorithm,
+/// returning the component of each node.
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+
+    let mut order = vec![UNVISITED; edges.len()];
+    let mut low = vec![0; edges.len()];
+    let mut on_stack = vec![false; edges.len()];
+    let mut stack = Vec::new();
+    let mut components = vec![UNVISITED; edges.len()];
+    let mut next_order = 0;
+    let mut next_component = 0;
+
+    for root in 0..edges.len() {
+        if order[root] != UNVISITED {
+            continue;
+        }
+
+        // Each frame holds a node and the position of its next edge to follow.
+        let mut frames = vec![(root, 0)];
+        order[root] = next_order;
+        low[root] = next_order;
+        next_order += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(frame) = frames.last_mut() {
+            let node = frame.0;
+
+            if let Some(&next) = edges[node].get(frame.1) {
+                frame.1 += 1;
+
+                if order[next] == UNVISITED {
+                    order[next] = next_order;
+                    low[next] = next_order;
+                    next_order += 1;
+                    stack.push(next);
+                    on_stack[next] = true;
+                    frames.push((next, 0));
+                } else if on_stack[next] {
+                    low[node] = low[node].min(order[next]);
+                }
+
+                continue;
+            }
+
+            frames.pop();
+
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[node]);
+            }
+
+            // The node is the first visited one of its component.
+            if low[node] == order[node] {
+                loop {
+                    let member = stack.pop().expect("Component members are on the stack.");
+                    on_stack[member] = false;
+                    components[member] = next_component;
+
+                    if member == node {
+                        break;
+                    }
+                }
+
+                next_component += 1;
+            }
+        }
+    }
+
+    components
+}
+
+/// Counts the models of clauses over the given sorted variables by enumerating all assignments.
+fn count_models(clauses: &[&Vec<Literal>], variables: &[u64]) -> u64 {
+    // Encode each clause by the bits of its positive and negative literals.
+    let masks: Vec<(u32, u32)> = clauses
+        .iter()
+        .map(|clause| {
+            clause.iter().fold((0, 0), |(positive, negative), literal| {
+                let bit = 1 << variables.binary_search(&literal.variable()).unwrap();
+
+                if literal.is_positive() {
+                    (positive | bit, negative)
+                } else {
+                    (positive, negative | bit)
+                }
+            })
+        })
+        .collect();
+
+    (0u32..1 << variables.len())
+        .filter(|assignment| {
+            masks.iter().all(|&(positive, negative)| {
+                assignment & positive != 0 || !assignment & negative != 0
+            })
+        })
+        .count() as u64
+}
+
+#[cfg(test)]
+mod test {
+    use crate::preprocess::{count_models, preprocess, Technique};
+    use clap::ValueEnum;
+    use hypergraph_formats::cnf::{Cnf, Literal};
+    use num::BigInt;
+
+    /// CNFs exercising each technique, each with its model count.
+    const CNFS: [(&str, u128); 7] = [
+        // Units propagating through a chain of implications.
+        ("p cnf 5 5\n1 0\n-1 2 0\n-2 3 4 0\n4 5 0\n-3 -5 0\n", 3),
+        // Variables `1`, `2` and `3` are equivalent, `5` and `6` occur nowhere.
+        ("p cnf 6 5\n-1 2 0\n-2 3 0\n-3 1 0\n1 4 0\n-4 -2 3 0\n", 12),
+        // Subsumed and duplicate clauses.
+        (
+            "p cnf 4 5\n1 2 0\n1 2 3 0\n1 2 0\n-1 -3 4 0\n2 -3 -4 0\n",
+            9,
+        ),
+        // Two small components besides the largest one.
+        (
+            "p cnf 9 7\n1 2 0\n-1 3 0\n4 5 0\n-5 6 7 0\n6 -7 0\n8 9 0\n-8 -9 0\n",
+            56,
+        ),
+        // A tautology and equivalent variables.
+        ("p cnf 3 4\n1 -1 2 0\n-2 3 0\n2 -3 0\n-2 -3 0\n", 2),
+        // A literal equivalent to its negation.
+        ("p cnf 3 4\n-1 2 0\n-2 -1 0\n1 3 0\n-3 1 0\n", 0),
+        // Contradicting units.
+        ("p cnf 2 3\n1 0\n-1 2 0\n-2 0\n", 0),
+    ];
+
+    /// Counts the models of a CNF in the DIMACS format by enumerating all assignments.
+    fn brute_force(input: &str) -> u128 {
+        let cnf: Cnf = input.parse().unwrap();
+        let clauses: Vec<Vec<Literal>> = cnf
+            .clauses()
+            .iter()
+            .map(|clause| clause.literals().copied().collect())
+            .collect();
+        let variables: Vec<u64> = (1..=cnf.num_vars()).collect();
+
+        u128::from(count_models(
+            &clauses.iter().collect::<Vec<_>>(),
+            &variables,
+        ))
+    }
+
+    /// Checks that the model count of each CNF is preserved up to the returned factor.
+    fn check(techniques: &[Technique]) {
+        for (input, count) in CNFS {
+            let cnf: Cnf = input.parse().unwrap();
+            let (preprocessed, factor) = preprocess(&cnf, techniques);
+
+            assert_eq!(brute_force(input), count);
+            assert_eq!(
+                factor * brute_force(&preprocessed.to_string()),
+                BigInt::from(count),
+                "Count not preserved for {input:?} using {techniques:?}."
+            );
+        }
+    }
+
+    #[test]
+    fn all_techniques() {
+        check(Technique::value_variants());
+    }
+
+    #[test]
+    fn single_technique() {
+        for &technique in Technique::value_variants() {
+            check(&[technique]);
+        }
+    }
+
+    #[test]
+    fn all_but_one_technique() {
+        for technique in Technique::value_variants() {
+            let techniques: Vec<Technique> = Technique::value_variants()
+                .iter()
+                .filter(|&other| other != technique)
+                .copied()
+                .collect();
+
+            check(&techniques);
+        }
+    }
+
+    #[test]
+    fn simplifies() {
+        let cnf: Cnf = CNFS[0].0.parse().unwrap();
+        let (preprocessed, factor) = preprocess(&cnf, Technique::value_variants());
+
+        // Variables `1` and `2` are forced, leaving `3 4`, `4 5` and `-3 -5`.
+        assert_eq!(preprocessed.num_vars(), 3);
+        assert_eq!(preprocessed.len(), 3);
+        assert_eq!(factor, BigInt::from(1));
+    }
+}
@@ -0,0 +1,279 @@
+use log::{trace, warn};
+use std::io::{self, Read};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Resource limits for running an external process.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Limits {
+    /// Maximum wall-clock time.
+    pub time: Option<Duration>,
+    /// Maximum size of the virtual memory in bytes.
+    pub memory: Option<u64>,
+}
+
+impl Limits {
+    /// Creates limits from a timeout in seconds and a memory limit in MiB.
+    pub fn new(time: Option<u64>, memory: Option<u64>) -> Self {
+        Self {
+            time: time.map(Duration::from_secs),
+            memory: memory.map(|memory| memory * 1024 * 1024),
+        }
+    }
+
+    /// Creates a copy of these limits with the given timeout in seconds.
+    pub fn with_time(self, time: Option<u64>) -> Self {
+        Self {
+            time: time.map(Duration::from_secs),
+            ..self
+        }
+    }
+}
+
+/// Resources used by a finished process.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Usage {
+    /// CPU time spent in user mode in ms.
+    pub time_user: u128,
+    /// CPU time spent in kernel mode in ms.
+    pub time_system: u128,
+    /// Peak resident set size in KiB, `0` if unavailable.
+    pub memory: u64,
+}
+
+impl From<&libc::rusage> for Usage {
+    fn from(value: &libc::rusage) -> Self {
+        let milliseconds =
+            |time: libc::timeval| time.tv_sec as u128 * 1000 + time.tv_usec as u128 / 1000;
+
+        Self {
+            time_user: milliseconds(value.ru_utime),
+            time_system: milliseconds(value.ru_stime),
+            memory: value.ru_maxrss as u64,
+        }
+    }
+}
+
+/// The result of running an external process.
+#[derive(Debug)]
+pub struct ProcessOutput {
+    /// The exit status of the process.
+    pub status: ExitStatus,
+    /// Everything the process wrote to stdout.
+    pub stdout: Vec<u8>,
+    /// Everything the process wrote to stderr.
+    pub stderr: Vec<u8>,
+    /// The wall-clock time taken in ms.
+    pub time: u128,
+    /// The resources used by the process.
+    pub usage: Usage,
+    /// Whether the process was killed because of reaching the time limit.
+    pub timed_out: bool,
+}
+
+/// Runs a command to completion while applying the given limits.
+///
+/// The process is started in its own process group which is killed as a whole once the time limit
+/// is reached, as well as after the process exited to not leave any descendants holding its output
+/// open. The memory limit is applied to the address space of the process.
+pub fn run(command: &mut Command, limits: Limits) -> io::Result<ProcessOutput> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let memory = limits.memory;
+
+    // SAFETY: Only async-signal-safe functions are called between forking and executing.
+    unsafe {
+        command.pre_exec(move || {
+            // Move the process into its own group to be able to kill all of its children.
+            if libc::setpgid(0, 0) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            if let Some(memory) = memory {
+                let limit = libc::rlimit {
+                    rlim_cur: memory as libc::rlim_t,
+                    rlim_max: memory as libc::rlim_t,
+                };
+
+                if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    trace!("{:?} (limits: {:?})", command, limits);
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+    let pid = child.id() as libc::pid_t;
+
+    // Drain the output in the background to prevent the process from blocking on full pipes.
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    // Kill the process group on a separate thread unless the process exits within the time limit.
+    let (exited, exit) = mpsc::channel::<()>();
+    let timer = limits.time.map(|time| {
+        thread::spawn(move || {
+            let timed_out = exit.recv_timeout(time) == Err(RecvTimeoutError::Timeout);
+
+            if timed_out {
+                warn!("Time limit reached, killing process {}.", pid);
+
+                // SAFETY: Sending a signal has no memory safety implications.
+                unsafe { libc::killpg(pid, libc::SIGKILL) };
+            }
+
+            timed_out
+        })
+    });
+
+    // Wait for the process to exit without reaping it, so its process group stays reserved.
+    // SAFETY: `siginfo_t` is a plain C struct for which all zeroes is a valid value.
+    let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+    // SAFETY: The pointer is valid for the duration of the call.
+    retry(|| unsafe {
+        libc::waitid(
+            libc::P_PID,
+            pid as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOWAIT,
+        )
+    })?;
+
+    drop(exited);
+    let timed_out = timer.is_some_and(|timer| timer.join().expect("Failed to run the timer."));
+
+    // Kill any descendants left behind, which would otherwise keep the pipes open.
+    // SAFETY: Sending a signal has no memory safety implications.
+    unsafe { libc::killpg(pid, libc::SIGKILL) };
+
+    let mut status = 0;
+    // SAFETY: `rusage` is a plain C struct for which all zeroes is a valid value.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+
+    // Reap the process.
+    // SAFETY: The pointers are valid for the duration of the call.
+    retry(|| unsafe { libc::wait4(pid, &mut status, 0, &mut rusage) })?;
+
+    let time = start.elapsed().as_millis();
+
+    Ok(ProcessOutput {
+        status: ExitStatus::from_raw(status),
+        stdout: stdout.join().expect("Failed to read stdout.")?,
+        stderr: stderr.join().expect("Failed to read stderr.")?,
+        time,
+        usage: Usage::from(&rusage),
+        timed_out,
+    })
+}
+
+/// Runs a function on the current thread while tracking the time and resources taken.
+///
+/// The CPU times are those of the current thread. The memory is reported as `0` since only the peak
+/// of the whole process is available, which is not specific to the function. Returns the wall-clock time taken in ms, the resources used and the result of the function.
+pub fn measure<T>(function: impl FnOnce() -> T) -> (u128, Usage, T) {
+    let before = thread_usage();
+    let start = Instant::now();
+
+    let result = function();
+
+    let time = start.elapsed().as_millis();
+    let after = thread_usage();
+
+    let usage = Usage {
+        time_user: after.time_user - before.time_user,
+        time_system: after.time_system - before.time_system,
+        memory: 0,
+    };
+
+    (time, usage, result)
+}
+
+/// Queries the resources used by the current thread so far.
+fn thread_usage() -> Usage {
+    // SAFETY: `rusage` is a plain C struct for which all zeroes is a valid value.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+
+    // SAFETY: The pointer is valid for the duration of the call.
+    if unsafe { libc::getrusage(libc::RUSAGE_THREAD, &mut rusage) } != 0 {
+        warn!(
+            "Failed to query resource usage: {}",
+            io::Error::last_os_error()
+        );
+    }
+
+    Usage::from(&rusage)
+}
+
+/// Repeats a system call as long as it is interrupted by a signal.
+fn retry(mut call: impl FnMut() -> libc::c_int) -> io::Result<libc::c_int> {
+    loop {
+        let result = call();
+
+        if result >= 0 {
+            return Ok(result);
+        }
+
+        let error = io::Error::last_os_error();
+
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+}
+
+/// Reads a pipe to its end on a separate thread.
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buffer)?;
+        }
+
+        Ok(buffer)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::process::{run, Limits};
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn time_limit() {
+        let limits = Limits::new(Some(1), None);
+        let output = run(Command::new("sleep").arg("10"), limits).unwrap();
+
+        assert!(output.timed_out);
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn descendants() {
+        // A background process keeps stdout open after the shell exits.
+        let start = Instant::now();
+        let output = run(
+            Command::new("sh").args(["-c", "sleep 10 & echo done"]),
+            Limits::default(),
+        )
+        .unwrap();
+
+        assert!(!output.timed_out);
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"done\n");
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
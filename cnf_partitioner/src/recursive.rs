@@ -0,0 +1,251 @@
+use crate::assignment::{find_assignments, Strategy};
+use crate::batch::Deadline;
+use crate::cnf::count_free_variables;
+use crate::d4::D4;
+use crate::partitioner::{Partitioner, PartitionerError};
+use crate::pipeline::{compile, invalid_data};
+use crate::process::Limits;
+use crate::refine::refine;
+use crate::Args;
+use hypergraph_formats::cnf::{cut_variables, Cnf, CnfError, VariableHeuristic};
+use hypergraph_formats::Graph;
+use log::{debug, warn};
+use num::BigInt;
+use serde::{Serialize, Serializer};
+use std::io;
+
+/// A node of the recursion tree, being a CNF either compiled as a leaf or split into its children.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Trace {
+    /// The level of the node, the root being at `0`.
+    pub depth: usize,
+    /// The amount of clauses of the CNF.
+    pub clauses: usize,
+    /// The amount of variables of the CNF.
+    pub variables: u64,
+    /// The size of the cut when splitting the CNF, `0` for leaves.
+    pub cut_size: usize,
+    /// The wall-clock time taken for partitioning the CNF in ms.
+    pub time_partitioning: u128,
+    /// The wall-clock time taken for compiling the CNF in ms, including attempts within the time
+    /// threshold, `u128::MAX` in case of a timeout.
+    pub time_compilation: u128,
+    /// The model count of the CNF conditioned on the cuts below it, `0` in case of a timeout.
+    #[serde(serialize_with = "serialize_count")]
+    pub count: BigInt,
+    /// The CNFs this one was split into.
+    pub children: Vec<Trace>,
+}
+
+impl Trace {
+    /// Whether compiling any of the leaves reached the time limit.
+    pub fn timed_out(&self) -> bool {
+        self.time_compilation == u128::MAX || self.children.iter().any(Trace::timed_out)
+    }
+
+    /// The total wall-clock time taken for partitioning and compiling in ms, `u128::MAX` in case of
+    /// a timeout.
+    pub fn time(&self) -> u128 {
+        self.children
+            .iter()
+            .map(Trace::time)
+            .fold(self.time_partitioning, u128::saturating_add)
+            .saturating_add(self.time_compilation)
+    }
+
+    /// The amount of levels below the root.
+    pub fn height(&self) -> usize {
+        self.children
+            .iter()
+            .map(|child| child.height() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The amount of CNFs compiled without being split.
+    pub fn leaves(&self) -> usize {
+        if self.children.is_empty() {
+            1
+        } else {
+            self.children.iter().map(Trace::leaves).sum()
+        }
+    }
+}
+
+/// Serializes a model count as a string as it might exceed any integer type.
+fn serialize_count<S: Serializer>(count: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&count.to_string())
+}
+
+/// Splits CNFs recursively with a single partitioner and heuristic.
+pub struct Recursion<'a> {
+    pub args: &'a Args,
+    pub d4: &'a D4,
+    pub partitioner: &'a dyn Partitioner,
+    pub heuristic: VariableHeuristic,
+    pub deadline: Deadline,
+}
+
+impl Recursion<'_> {
+    /// Creates the root of the recursion tree from a CNF already split into the given conditioned
+    /// parts, counting each part recursively.
+    pub fn root(
+        &self,
+        cnf: &Cnf,
+        parts: &[Cnf],
+        cut_size: usize,
+        time_partitioning: u128,
+    ) -> io::Result<Trace> {
+        let trace = Trace {
+            clauses: cnf.len(),
+            variables: cnf.num_vars(),
+            cut_size,
+            time_partitioning,
+            ..Default::default()
+        };
+
+        self.split(cnf, parts, trace)
+    }
+
+    /// Counts the models of a CNF by splitting it recursively, conditioning the parts on the cut
+    /// at each level.
+    ///
+    /// A CNF is compiled instead of being split if it has at most the threshold of clauses, if d4
+    /// compiles it within the time threshold, if the maximum depth is reached, or if the partitioner
+    /// reaches its time limit.
+    pub fn count(&self, cnf: &Cnf, depth: usize) -> io::Result<Trace> {
+        self.deadline.check()?;
+
+        let mut trace = Trace {
+            depth,
+            clauses: cnf.len(),
+            variables: cnf.num_vars(),
+            ..Default::default()
+        };
+
+        // Partitioning fewer clauses than blocks is pointless.
+        let threshold = self.args.recursive_clauses.max(self.partitioner.blocks());
+
+        if cnf.len() <= threshold || depth >= self.args.recursive_depth {
+            return self.compile(cnf, trace);
+        }
+
+        // Try to compile the CNF within the time threshold first.
+        if let Some(timeout) = self.args.recursive_timeout {
+            let compilation = compile(self.d4, cnf, None, Some(timeout), self.deadline, false)?;
+
+            if !compilation.timed_out() {
+                trace.time_compilation = compilation.time;
+                trace.count = compilation.count;
+                return Ok(trace);
+            }
+
+            trace.time_compilation = u128::from(timeout) * 1000;
+        }
+
+        // Generate the partition.
+        let graph = Graph::from((cnf, self.heuristic)).dual();
+        let limits = Limits::new(
+            self.deadline.limit(self.args.partitioner_timeout)?,
+            self.args.memory_limit,
+        );
+        let (time, _, mut partition) = match self.partitioner.run(&graph, limits) {
+            Ok(result) => result,
+            // Compile the CNF as a leaf instead of splitting it.
+            Err(PartitionerError::Timeout) => {
+                debug!("The partitioner reached the time limit at depth {depth}.");
+                return self.compile(cnf, trace);
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        // Reject partitions of external tools assigning blocks outside the requested ones.
+        cnf.check_partition(partition.as_slice(), self.partitioner.blocks())
+            .map_err(invalid_data)?;
+
+        if self.args.refine {
+            let epsilon = self
+                .args
+                .refine_epsilon
+                .unwrap_or(self.partitioner.epsilon());
+            refine(cnf, &mut partition, self.partitioner.blocks(), epsilon);
+        }
+
+        trace.time_partitioning = time;
+
+        let parts = cnf
+            .split(partition.as_slice(), self.partitioner.blocks())
+            .map_err(invalid_data)?
+            .parts;
+
+        // A partition not splitting the CNF would recurse forever.
+        if parts.iter().any(|part| part.len() == cnf.len()) {
+            debug!("The partition did not split the CNF at depth {depth}.");
+            return self.compile(cnf, trace);
+        }
+
+        let cut = cut_variables(&parts);
+        trace.cut_size = cut.len();
+
+        // Only a single assignment is conditioned on, being the first one when sampling.
+        let strategy = match self.args.assignment_strategy {
+            Strategy::Sample => Strategy::Random,
+            strategy => strategy,
+        };
+
+        // Without an assignment, the CNF has no models.
+        let Some(assignment) = find_assignments(
+            cnf,
+            &cut,
+            strategy,
+            self.args.assignment_samples,
+            self.partitioner.seed(),
+        )
+        .into_iter()
+        .next() else {
+            return Ok(trace);
+        };
+
+        let parts = parts
+            .iter()
+            .map(|part| part.condition(&assignment))
+            .collect::<Result<Vec<Cnf>, CnfError>>()
+            .map_err(invalid_data)?;
+
+        self.split(cnf, &parts, trace)
+    }
+
+    /// Counts each of the conditioned parts of a CNF recursively, multiplying their counts.
+    fn split(&self, cnf: &Cnf, parts: &[Cnf], mut trace: Trace) -> io::Result<Trace> {
+        // Variables occurring in no part are free.
+        trace.count = BigInt::from(1) << count_free_variables(cnf, parts);
+
+        for part in parts {
+            // Renumber its variables densely.
+            let (part, _) = part.compact();
+            let child = self.count(&part, trace.depth + 1)?;
+
+            trace.count *= &child.count;
+            trace.children.push(child);
+        }
+
+        Ok(trace)
+    }
+
+    /// Compiles a CNF as a leaf of the recursion.
+    fn compile(&self, cnf: &Cnf, mut trace: Trace) -> io::Result<Trace> {
+        let compilation = compile(self.d4, cnf, None, self.args.timeout, self.deadline, false)?;
+
+        if compilation.timed_out() {
+            warn!(
+                "Timeout reached while compiling a leaf at depth {}.",
+                trace.depth
+            );
+        }
+
+        trace.time_compilation = trace.time_compilation.saturating_add(compilation.time);
+        trace.count = compilation.count;
+
+        Ok(trace)
+    }
+}
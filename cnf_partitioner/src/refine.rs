@@ -0,0 +1,156 @@
+use hypergraph::Partition;
+use hypergraph_formats::cnf::Cnf;
+use std::collections::BTreeSet;
+
+/// The result of refining a partition of the clauses of a CNF.
+#[derive(Debug, Clone, Default)]
+pub struct Refinement {
+    /// The amount of variables shared between blocks before refining.
+    pub cut_before: usize,
+    /// The amount of variables shared between blocks after refining.
+    pub cut_after: usize,
+    /// The amount of clauses moved to another block.
+    pub moves: usize,
+}
+
+/// Refines a partition of the clauses of a CNF by greedily moving single clauses to other blocks
+/// as long as this reduces the amount of variables shared between blocks.
+///
+/// No block receives a clause if it would exceed the ideal block size of `⌈clauses / blocks⌉` by
+/// more than the relative imbalance `epsilon`, and no block gives away its last clause.
+pub fn refine(cnf: &Cnf, partition: &mut Partition, blocks: usize, epsilon: f64) -> Refinement {
+    // The distinct variables of each clause.
+    let clauses: Vec<Vec<u64>> = cnf
+        .clauses()
+        .iter()
+        .map(|clause| {
+            clause
+                .variables()
+                .collect::<BTreeSet<u64>>()
+                .into_iter()
+                .collect()
+        })
+        .collect();
+
+    // The amount of clauses of each block containing a variable.
+    let mut occurrences = vec![vec![0usize; blocks]; cnf.num_vars() as usize + 1];
+
+    for (clause, variables) in clauses.iter().enumerate() {
+        for &variable in variables {
+            occurrences[variable as usize][partition[clause]] += 1;
+        }
+    }
+
+    let mut sizes = partition.block_sizes(blocks);
+    let ideal = clauses.len().div_ceil(blocks);
+    let limit = ((1.0 + epsilon) * ideal as f64).floor() as usize;
+
+    let cut = |occurrences: &[Vec<usize>]| {
+        occurrences
+            .iter()
+            .filter(|blocks| blocks.iter().filter(|&&count| count > 0).count() > 1)
+            .count()
+    };
+
+    let mut refinement = Refinement {
+        cut_before: cut(&occurrences),
+        ..Default::default()
+    };
+
+    // Repeat passes over all clauses until no move improves the cut, each move reducing it.
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+
+        for (clause, variables) in clauses.iter().enumerate() {
+            let from = partition[clause];
+
+            if sizes[from] <= 1 {
+                continue;
+            }
+
+            // Find the target block reducing the cut the most.
+            let best = (0..blocks)
+                .filter(|&to| to != from && sizes[to] < limit)
+                .map(|to| (gain(&occurrences, variables, from, to), to))
+                .filter(|&(gain, _)| gain > 0)
+                .max_by_key(|&(gain, to)| (gain, std::cmp::Reverse(to)));
+
+            let Some((_, to)) = best else {
+                continue;
+            };
+
+            for &variable in variables {
+                occurrences[variable as usize][from] -= 1;
+                occurrences[variable as usize][to] += 1;
+            }
+
+            sizes[from] -= 1;
+            sizes[to] += 1;
+            partition[clause] = to;
+
+            refinement.moves += 1;
+            improved = true;
+        }
+    }
+
+    refinement.cut_after = cut(&occurrences);
+    refinement
+}
+
+/// Calculates by how much moving a clause with the given variables between two blocks reduces
+/// the cut.
+fn gain(occurrences: &[Vec<usize>], variables: &[u64], from: usize, to: usize) -> isize {
+    variables
+        .iter()
+        .map(|&variable| {
+            let counts = &occurrences[variable as usize];
+            let before = counts.iter().filter(|&&count| count > 0).count();
+            let after = before - usize::from(counts[from] == 1) + usize::from(counts[to] == 0);
+
+            isize::from(before > 1) - isize::from(after > 1)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::refine::refine;
+    use hypergraph::Partition;
+    use hypergraph_formats::cnf::{cut_variables, Cnf};
+
+    /// Two chains of clauses, `1 2 3` and `4 5 6`, with a partition cutting both.
+    const CNF: &str = "p cnf 6 4\n1 2 0\n2 3 0\n4 5 0\n5 6 0\n";
+
+    #[test]
+    fn refine_cut() {
+        let cnf: Cnf = CNF.parse().unwrap();
+        let mut partition = Partition::from(vec![0, 1, 0, 1]);
+
+        let refinement = refine(&cnf, &mut partition, 2, 0.5);
+
+        assert_eq!(refinement.cut_before, 2);
+        assert_eq!(refinement.cut_after, 0);
+        assert_eq!(refinement.moves, 2);
+        assert_eq!(partition.as_slice(), [1, 1, 0, 0]);
+
+        // The reported cut is the one of the refined partition.
+        let parts = cnf.split(partition.as_slice(), 2).unwrap().parts;
+        assert!(cut_variables(&parts).is_empty());
+    }
+
+    #[test]
+    fn imbalance_bound() {
+        let cnf: Cnf = CNF.parse().unwrap();
+        let mut partition = Partition::from(vec![0, 1, 0, 1]);
+
+        // Every improving move would exceed the ideal block size.
+        let refinement = refine(&cnf, &mut partition, 2, 0.0);
+
+        assert_eq!(refinement.cut_before, 2);
+        assert_eq!(refinement.cut_after, 2);
+        assert_eq!(refinement.moves, 0);
+        assert_eq!(partition.as_slice(), [0, 1, 0, 1]);
+    }
+}
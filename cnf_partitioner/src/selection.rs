@@ -0,0 +1,129 @@
+use crate::partitioner::Partitioner;
+use clap::ValueEnum;
+use hypergraph_formats::cnf::VariableHeuristic;
+use std::collections::BTreeMap;
+use strum::Display;
+
+/// What to minimize when selecting the amount of blocks automatically.
+#[derive(Debug, Display, Copy, Clone, Default, PartialEq, ValueEnum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Criterion {
+    /// The size of the cut set.
+    #[default]
+    Cut,
+    /// The total time of compiling the split CNFs.
+    Time,
+    /// The estimated time of compiling the split CNFs for all satisfying cut assignments.
+    Estimated,
+}
+
+/// The values of the criterion per amount of blocks tried for a configuration.
+#[derive(Debug, Clone, Default)]
+pub struct Trajectory {
+    /// The amounts of blocks tried in ascending order along with the values of the criterion.
+    pub points: Vec<(usize, f64)>,
+    /// Whether the last amount of blocks did not improve enough, so no more are tried.
+    pub stopped: bool,
+}
+
+impl Trajectory {
+    /// The amount of blocks with the best value of the criterion, preferring fewer blocks on ties.
+    pub fn chosen(&self) -> Option<usize> {
+        self.points
+            .iter()
+            .min_by(|(_, first), (_, second)| first.total_cmp(second))
+            .map(|&(blocks, _)| blocks)
+    }
+
+    /// The best value of the criterion so far.
+    fn best(&self) -> Option<f64> {
+        self.points
+            .iter()
+            .map(|&(_, value)| value)
+            .min_by(f64::total_cmp)
+    }
+}
+
+/// Configurations being the same except for their amount of blocks.
+type Key = (String, String, String, u64);
+
+/// Selects the amount of blocks per configuration by growing it until the criterion stops
+/// improving.
+///
+/// Configurations only differ in their amount of blocks in case they share their partitioner,
+/// heuristic, epsilon and seed. Each amount of blocks has to improve on the best value so far by
+/// the given factor, a factor of `0.9` requiring more than 10% less.
+#[derive(Debug, Clone)]
+pub struct Selection {
+    criterion: Criterion,
+    factor: f64,
+    trajectories: BTreeMap<Key, Trajectory>,
+}
+
+impl Selection {
+    /// Creates a selection without any amounts of blocks tried.
+    pub fn new(criterion: Criterion, factor: f64) -> Self {
+        Self {
+            criterion,
+            factor,
+            trajectories: BTreeMap::new(),
+        }
+    }
+
+    /// The criterion to minimize.
+    pub fn criterion(&self) -> Criterion {
+        self.criterion
+    }
+
+    /// Whether the configuration of a partitioner stopped improving, so it should be skipped.
+    pub fn stopped(&self, partitioner: &dyn Partitioner, heuristic: VariableHeuristic) -> bool {
+        self.trajectory(
+            partitioner.name_short(),
+            heuristic,
+            partitioner.epsilon(),
+            partitioner.seed(),
+        )
+        .is_some_and(|trajectory| trajectory.stopped)
+    }
+
+    /// Adds the value of the criterion for the amount of blocks of a partitioner.
+    pub fn add(&mut self, partitioner: &dyn Partitioner, heuristic: VariableHeuristic, value: f64) {
+        let trajectory = self
+            .trajectories
+            .entry(key(
+                partitioner.name_short(),
+                heuristic,
+                partitioner.epsilon(),
+                partitioner.seed(),
+            ))
+            .or_default();
+
+        trajectory.stopped = trajectory
+            .best()
+            .is_some_and(|best| value >= best * self.factor);
+
+        trajectory.points.push((partitioner.blocks(), value));
+    }
+
+    /// The trajectory of a configuration, if any amount of blocks was tried.
+    pub fn trajectory(
+        &self,
+        partitioner: &str,
+        heuristic: VariableHeuristic,
+        epsilon: f64,
+        seed: u64,
+    ) -> Option<&Trajectory> {
+        self.trajectories
+            .get(&key(partitioner, heuristic, epsilon, seed))
+    }
+}
+
+/// Identifies a configuration regardless of its amount of blocks.
+fn key(partitioner: &str, heuristic: VariableHeuristic, epsilon: f64, seed: u64) -> Key {
+    (
+        partitioner.to_string(),
+        heuristic.to_string(),
+        epsilon.to_string(),
+        seed,
+    )
+}
@@ -0,0 +1,264 @@
+use crate::nnf::{Edge, Kind, Nnf};
+use crate::NnfError;
+use std::collections::BTreeSet;
+
+/// The NNF encoded into a SAT solver, each node and edge being represented by a variable implying
+/// its sub-circuit.
+struct Encoding {
+    solver: cadical::Solver,
+    /// The variable of the first node.
+    nodes: i32,
+    /// The variable of the first edge of each node.
+    edges: Vec<i32>,
+}
+
+impl Encoding {
+    /// The variable of an edge given by its node and its position.
+    fn edge(&self, node: usize, position: usize) -> i32 {
+        self.edges[node] + position as i32
+    }
+}
+
+impl Nnf {
+    /// Checks that the NNF is a d-DNNF, being decomposable and deterministic.
+    pub fn validate(&self) -> Result<(), NnfError> {
+        self.check_decomposability()?;
+        self.check_determinism()
+    }
+
+    /// Checks that the children of each conjunction share no variables.
+    ///
+    /// The literals of an edge are conjoined with its child and must not share variables with it
+    /// either.
+    pub fn check_decomposability(&self) -> Result<(), NnfError> {
+        let variables = self.variables();
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let error = |variable| NnfError::NotDecomposable {
+                node: self.ids[index],
+                variable,
+            };
+
+            // The variables of the previous children of a conjunction.
+            let mut seen: BTreeSet<u64> = BTreeSet::new();
+
+            for edge in &node.children {
+                let child = &variables[edge.child];
+                let mut literals = BTreeSet::new();
+
+                for variable in edge.literals.iter().map(|literal| literal.unsigned_abs()) {
+                    if child.contains(&variable) || !literals.insert(variable) {
+                        return Err(error(variable));
+                    }
+                }
+
+                if node.kind != Kind::And {
+                    continue;
+                }
+
+                if let Some(&variable) = child.iter().chain(&literals).find(|&v| seen.contains(v)) {
+                    return Err(error(variable));
+                }
+
+                seen.extend(child.iter().chain(&literals));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the children of each disjunction share no models.
+    pub fn check_determinism(&self) -> Result<(), NnfError> {
+        self.check_determinism_within(None)
+    }
+
+    /// Checks determinism while only considering the variables in the given scope, if any.
+    ///
+    /// Literals of all other variables are forgotten by treating them as true.
+    pub(crate) fn check_determinism_within(
+        &self,
+        scope: Option<&BTreeSet<u64>>,
+    ) -> Result<(), NnfError> {
+        let relevant =
+            |literal: i64| scope.is_none_or(|scope| scope.contains(&literal.unsigned_abs()));
+
+        // The solver is only set up once a pair of children is not trivially disjoint.
+        let mut encoding = None;
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            if !matches!(node.kind, Kind::Or { .. }) {
+                continue;
+            }
+
+            let implied: Vec<BTreeSet<i64>> = node
+                .children
+                .iter()
+                .map(|edge| self.implied(edge, relevant))
+                .collect();
+
+            for first in 0..node.children.len() {
+                for second in first + 1..node.children.len() {
+                    // Children implying complementary literals share no models.
+                    if implied[first]
+                        .iter()
+                        .any(|literal| implied[second].contains(&-literal))
+                    {
+                        continue;
+                    }
+
+                    let encoding = encoding.get_or_insert_with(|| self.encode(relevant));
+                    let assumptions = [encoding.edge(index, first), encoding.edge(index, second)];
+
+                    if encoding.solver.solve_with(assumptions) == Some(true) {
+                        return Err(NnfError::NotDeterministic {
+                            node: self.ids[index],
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Collects the relevant literals directly implied by an edge.
+    ///
+    /// These are its own literals as well as its child if being a literal or the literals of its
+    /// child if being a conjunction.
+    fn implied(&self, edge: &Edge, relevant: impl Fn(i64) -> bool) -> BTreeSet<i64> {
+        let child = &self.nodes[edge.child];
+
+        let mut implied: BTreeSet<i64> = edge.literals.iter().copied().collect();
+
+        match child.kind {
+            Kind::Literal(literal) => {
+                implied.insert(literal);
+            }
+            Kind::And => {
+                for edge in &child.children {
+                    implied.extend(&edge.literals);
+
+                    if let Kind::Literal(literal) = self.nodes[edge.child].kind {
+                        implied.insert(literal);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        implied.retain(|&literal| relevant(literal));
+        implied
+    }
+
+    /// Encodes the NNF into a SAT solver.
+    ///
+    /// Each node and edge only implies its sub-circuit, which suffices as an NNF is monotone in
+    /// them.
+    fn encode(&self, relevant: impl Fn(i64) -> bool) -> Encoding {
+        let nodes = self.num_vars as i32 + 1;
+
+        let mut edges = Vec::with_capacity(self.nodes.len());
+        let mut next = nodes + self.nodes.len() as i32;
+
+        for node in &self.nodes {
+            edges.push(next);
+            next += node.children.len() as i32;
+        }
+
+        let mut encoding = Encoding {
+            solver: Default::default(),
+            nodes,
+            edges,
+        };
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let variable = encoding.nodes + index as i32;
+            let edges = (0..node.children.len()).map(|position| encoding.edge(index, position));
+
+            match node.kind {
+                Kind::Literal(literal) if relevant(literal) => {
+                    encoding.solver.add_clause([-variable, literal as i32])
+                }
+                Kind::Literal(_) | Kind::True => {}
+                Kind::False => encoding.solver.add_clause([-variable]),
+                Kind::And => {
+                    let clauses: Vec<[i32; 2]> = edges.map(|edge| [-variable, edge]).collect();
+                    clauses
+                        .into_iter()
+                        .for_each(|clause| encoding.solver.add_clause(clause));
+                }
+                Kind::Or { .. } => {
+                    let clause: Vec<i32> = [-variable].into_iter().chain(edges).collect();
+                    encoding.solver.add_clause(clause);
+                }
+            }
+
+            // Each edge implies its child and its literals.
+            for (position, edge) in node.children.iter().enumerate() {
+                let variable = encoding.edge(index, position);
+                let child = encoding.nodes + edge.child as i32;
+
+                encoding.solver.add_clause([-variable, child]);
+
+                for &literal in edge.literals.iter().filter(|&&literal| relevant(literal)) {
+                    encoding.solver.add_clause([-variable, literal as i32]);
+                }
+            }
+        }
+
+        encoding
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Nnf, NnfError};
+
+    #[test]
+    fn decomposability() {
+        let valid: Nnf = "nnf 4 2 2\nL 1\nL 2\nA 2 0 1\n".parse().unwrap();
+        assert!(valid.check_decomposability().is_ok());
+
+        let conjunction: Nnf = "nnf 4 2 1\nL 1\nL -1\nA 2 0 1\n".parse().unwrap();
+        assert!(matches!(
+            conjunction.check_decomposability(),
+            Err(NnfError::NotDecomposable {
+                node: 2,
+                variable: 1
+            })
+        ));
+
+        let edge: Nnf = "a 1 0\nt 2 0\n1 2 1 -1 0\n".parse().unwrap();
+        assert!(matches!(
+            edge.check_decomposability(),
+            Err(NnfError::NotDecomposable { node: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn determinism() {
+        // Decided by the literals on the edges.
+        let decision: Nnf = "o 1 0\nt 2 0\n1 2 1 0\n1 2 -1 2 0\n".parse().unwrap();
+        assert!(decision.validate().is_ok());
+
+        // `1 or 2` shares the model `1 2`.
+        let overlapping: Nnf = "o 1 0\nt 2 0\n1 2 1 0\n1 2 2 0\n".parse().unwrap();
+        assert!(matches!(
+            overlapping.validate(),
+            Err(NnfError::NotDeterministic { node: 1 })
+        ));
+
+        // `(1 or (-1 and 2)) and 3` excludes `-1 and -2` but shares models with `-1 and 3`, which is
+        // not decided by the literals of the children.
+        let nested = |last: &str| {
+            format!("nnf 10 10 3\nL 1\nL -1\nL 2\nA 2 1 2\nO 0 2 0 3\nL 3\nA 2 4 5\nL -2\n{last}\nO 0 2 6 8\n")
+                .parse::<Nnf>()
+                .unwrap()
+        };
+        assert!(nested("A 2 1 7").validate().is_ok());
+        assert!(matches!(
+            nested("A 2 1 5").validate(),
+            Err(NnfError::NotDeterministic { node: 9 })
+        ));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::nnf::{Edge, Kind, Nnf, Node};
+use std::collections::BTreeSet;
+
+/// Builds an NNF out of others, e.g. by conjoining d-DNNFs sharing no variables and deciding
+/// between the results.
+#[derive(Debug, Default)]
+pub struct Composer {
+    nodes: Vec<Node>,
+}
+
+impl Composer {
+    /// Creates a composer without any nodes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all nodes of an NNF, returning the node being its root.
+    pub fn add(&mut self, nnf: &Nnf) -> usize {
+        let offset = self.nodes.len();
+
+        self.nodes.extend(nnf.nodes.iter().map(|node| {
+            Node {
+                kind: node.kind.clone(),
+                children: node
+                    .children
+                    .iter()
+                    .map(|edge| Edge {
+                        child: edge.child + offset,
+                        literals: edge.literals.clone(),
+                    })
+                    .collect(),
+            }
+        }));
+
+        offset + nnf.root
+    }
+
+    /// Adds a node being true or false.
+    pub fn constant(&mut self, value: bool) -> usize {
+        self.push(if value { Kind::True } else { Kind::False }, Vec::new())
+    }
+
+    /// Adds the conjunction of the given nodes, being true for no nodes.
+    pub fn conjoin(&mut self, children: &[usize]) -> usize {
+        if children.is_empty() {
+            return self.constant(true);
+        }
+
+        let children = children
+            .iter()
+            .map(|&child| Edge {
+                child,
+                literals: Vec::new(),
+            })
+            .collect();
+
+        self.push(Kind::And, children)
+    }
+
+    /// Adds a decision on a variable, leading to the first node if it is true and to the second
+    /// one otherwise.
+    pub fn decide(&mut self, variable: u64, positive: usize, negative: usize) -> usize {
+        let children = vec![
+            Edge {
+                child: positive,
+                literals: vec![variable as i64],
+            },
+            Edge {
+                child: negative,
+                literals: vec![-(variable as i64)],
+            },
+        ];
+
+        self.push(
+            Kind::Or {
+                decision: Some(variable),
+            },
+            children,
+        )
+    }
+
+    /// Creates the NNF rooted at the given node.
+    pub fn finish(self, root: usize) -> Nnf {
+        let ids = (0..self.nodes.len()).collect();
+
+        Nnf::new(self.nodes, root, ids, None).expect("Composed NNFs are acyclic.")
+    }
+
+    /// Adds a node, returning its index.
+    fn push(&mut self, kind: Kind, children: Vec<Edge>) -> usize {
+        self.nodes.push(Node { kind, children });
+        self.nodes.len() - 1
+    }
+}
+
+impl Nnf {
+    /// Renames the variables as given by a variable map, variable `v` becoming `variables[v - 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a variable of the NNF is not mapped.
+    pub fn rename(&self, variables: &[u64]) -> Nnf {
+        let rename = |literal: i64| {
+            let variable = variables[literal.unsigned_abs() as usize - 1] as i64;
+            literal.signum() * variable
+        };
+
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| Node {
+                kind: match node.kind {
+                    Kind::Literal(literal) => Kind::Literal(rename(literal)),
+                    Kind::Or {
+                        decision: Some(variable),
+                    } => Kind::Or {
+                        decision: Some(variables[variable as usize - 1]),
+                    },
+                    ref kind => kind.clone(),
+                },
+                children: node
+                    .children
+                    .iter()
+                    .map(|edge| Edge {
+                        child: edge.child,
+                        literals: edge
+                            .literals
+                            .iter()
+                            .map(|&literal| rename(literal))
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Nnf::new(nodes, self.root, self.ids.clone(), None).expect("Renaming keeps an NNF acyclic.")
+    }
+
+    /// Conditions the NNF on an assignment, replacing its literals by constants.
+    ///
+    /// Edges with a falsified literal lead to an added false node instead.
+    pub fn condition(&self, assignment: &[i64]) -> Nnf {
+        let assignment: BTreeSet<i64> = assignment.iter().copied().collect();
+        let value = |literal: i64| {
+            if assignment.contains(&literal) {
+                Some(true)
+            } else if assignment.contains(&-literal) {
+                Some(false)
+            } else {
+                None
+            }
+        };
+
+        let mut nodes = self.nodes.clone();
+        let mut ids = self.ids.clone();
+        let falsified = nodes.len();
+
+        for node in &mut nodes {
+            match node.kind {
+                Kind::Literal(literal) => match value(literal) {
+                    Some(true) => node.kind = Kind::True,
+                    Some(false) => node.kind = Kind::False,
+                    None => {}
+                },
+                Kind::Or {
+                    decision: Some(variable),
+                } if value(variable as i64).is_some() => node.kind = Kind::Or { decision: None },
+                _ => {}
+            }
+
+            for edge in &mut node.children {
+                if edge
+                    .literals
+                    .iter()
+                    .any(|&literal| value(literal) == Some(false))
+                {
+                    edge.child = falsified;
+                    edge.literals.clear();
+                } else {
+                    edge.literals.retain(|&literal| value(literal).is_none());
+                }
+            }
+        }
+
+        // Only add the false node if any edge leads to it.
+        if nodes
+            .iter()
+            .flat_map(|node| &node.children)
+            .any(|edge| edge.child == falsified)
+        {
+            nodes.push(Node {
+                kind: Kind::False,
+                children: Vec::new(),
+            });
+            ids.push(ids.iter().max().map_or(0, |&id| id + 1));
+        }
+
+        Nnf::new(nodes, self.root, ids, Some(self.num_vars))
+            .expect("Conditioning keeps an NNF acyclic.")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Composer, Nnf};
+    use num::BigInt;
+
+    /// The d-DNNF `(1 and 2) or (-1 and 3)` in the d4 format.
+    const D4: &str = "o 1 0\nt 2 0\n1 2 1 2 0\n1 2 -1 3 0\n";
+
+    #[test]
+    fn condition() {
+        let nnf: Nnf = D4.parse().unwrap();
+
+        // Conditioned variables no longer occur, being free.
+        assert_eq!(nnf.condition(&[1]).count(3), BigInt::from(4));
+        assert_eq!(nnf.condition(&[1, -2]).count(3), BigInt::from(0));
+        assert_eq!(nnf.condition(&[-1, 2]).count(3), BigInt::from(4));
+        assert!(nnf.condition(&[3]).validate().is_ok());
+    }
+
+    #[test]
+    fn rename() {
+        let nnf: Nnf = D4.parse::<Nnf>().unwrap().rename(&[4, 5, 6]);
+
+        assert_eq!(nnf.num_vars(), 6);
+        assert_eq!(nnf.count(6), BigInt::from(32));
+        assert_eq!(nnf.condition(&[-4, -6]).count(6), BigInt::from(0));
+    }
+
+    #[test]
+    fn compose() {
+        let nnf: Nnf = D4.parse().unwrap();
+        let mut composer = Composer::new();
+
+        // `4 -> ((1 and 2) or (-1 and 3)) and 5` with `-4 -> 6`.
+        let left = composer.add(&nnf);
+        let right = composer.add(&"nnf 1 0 5\nL 5\n".parse().unwrap());
+        let positive = composer.conjoin(&[left, right]);
+        let negative = composer.add(&"nnf 1 0 6\nL 6\n".parse().unwrap());
+        let root = composer.decide(4, positive, negative);
+
+        let composed = composer.finish(root);
+
+        assert!(composed.validate().is_ok());
+        assert_eq!(composed.count(6), BigInt::from(8 + 16));
+        assert_eq!(composed.metrics().decisions, 2);
+
+        // Writing it in the d4 format keeps its models.
+        let written: Nnf = composed.to_string().parse().unwrap();
+        assert!(written.validate().is_ok());
+        assert_eq!(written.count(6), BigInt::from(24));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::nnf::{Kind, Nnf};
+use crate::NnfError;
+use num::BigInt;
+use std::collections::BTreeSet;
+
+impl Nnf {
+    /// Counts the models over the variables `1..=num_vars`, extended to cover those of the NNF.
+    ///
+    /// The NNF is assumed to be a d-DNNF, see [`Nnf::validate`].
+    pub fn count(&self, num_vars: u64) -> BigInt {
+        self.weighted_count(num_vars, |_| BigInt::from(1))
+    }
+
+    /// Sums up the weights of all models, the weight of a model being the product of the weights
+    /// of its literals.
+    ///
+    /// The NNF is assumed to be a d-DNNF, see [`Nnf::validate`].
+    pub fn weighted_count(&self, num_vars: u64, weight: impl Fn(i64) -> BigInt) -> BigInt {
+        self.evaluate(num_vars, &weight, |variable| {
+            weight(variable as i64) + weight(-(variable as i64))
+        })
+    }
+
+    /// Counts the assignments to the given variables that can be extended to a model.
+    ///
+    /// Forgetting all other variables might break determinism, which is therefore checked.
+    /// Decomposability is still assumed.
+    pub fn projected_count(&self, variables: &BTreeSet<u64>) -> Result<BigInt, NnfError> {
+        self.check_determinism_within(Some(variables))?;
+
+        let num_vars = variables.last().copied().unwrap_or(0);
+
+        Ok(self.evaluate(
+            num_vars,
+            |_| BigInt::from(1),
+            |variable| BigInt::from(if variables.contains(&variable) { 2 } else { 1 }),
+        ))
+    }
+
+    /// Evaluates the NNF bottom up given the weights of the literals.
+    ///
+    /// Variables not occurring below a child of a disjunction are free for it, contributing the
+    /// given factor. The variables below a node are only kept until all of its parents are
+    /// evaluated, and only enumerated for disjunctions whose children do not all cover them.
+    fn evaluate(
+        &self,
+        num_vars: u64,
+        weight: impl Fn(i64) -> BigInt,
+        free: impl Fn(u64) -> BigInt,
+    ) -> BigInt {
+        let mut parents = vec![0; self.nodes.len()];
+
+        self.nodes
+            .iter()
+            .flat_map(|node| &node.children)
+            .for_each(|edge| parents[edge.child] += 1);
+
+        let mut scopes = vec![BTreeSet::new(); self.nodes.len()];
+        let mut values = vec![BigInt::ZERO; self.nodes.len()];
+
+        // The factor of all variables in the scope of a node but not below one of its edges.
+        let smooth = |scope: &BTreeSet<u64>, child: &BTreeSet<u64>, literals: &[i64]| -> BigInt {
+            let literals: BTreeSet<u64> = literals
+                .iter()
+                .map(|literal| literal.unsigned_abs())
+                .filter(|variable| !child.contains(variable))
+                .collect();
+
+            if scope.len() == child.len() + literals.len() {
+                return BigInt::from(1);
+            }
+
+            scope
+                .iter()
+                .filter(|variable| !child.contains(variable) && !literals.contains(variable))
+                .map(|&variable| free(variable))
+                .product()
+        };
+
+        // Children are considered before their parents.
+        for &index in self.order.iter().rev() {
+            let node = &self.nodes[index];
+
+            let mut scope = match node.kind {
+                Kind::Literal(literal) => BTreeSet::from([literal.unsigned_abs()]),
+                _ => BTreeSet::new(),
+            };
+
+            for edge in &node.children {
+                scope.extend(&scopes[edge.child]);
+                scope.extend(edge.literals.iter().map(|literal| literal.unsigned_abs()));
+            }
+
+            // Each edge conjoins its literals with its child.
+            let edges = node.children.iter().map(|edge| {
+                let value = edge
+                    .literals
+                    .iter()
+                    .map(|&literal| weight(literal))
+                    .product::<BigInt>()
+                    * &values[edge.child];
+
+                (edge, value)
+            });
+
+            values[index] = match node.kind {
+                Kind::Literal(literal) => weight(literal),
+                Kind::True => BigInt::from(1),
+                Kind::False => BigInt::ZERO,
+                Kind::And => edges.map(|(_, value)| value).product(),
+                Kind::Or { .. } => edges
+                    .map(|(edge, value)| {
+                        value * smooth(&scope, &scopes[edge.child], &edge.literals)
+                    })
+                    .sum(),
+            };
+
+            // Release the scopes no other parent needs anymore.
+            for edge in &node.children {
+                parents[edge.child] -= 1;
+
+                if parents[edge.child] == 0 {
+                    scopes[edge.child] = BTreeSet::new();
+                }
+            }
+
+            scopes[index] = scope;
+        }
+
+        // Variables not occurring in the NNF at all are free.
+        let all = (1..=num_vars.max(self.num_vars)).collect();
+
+        smooth(&all, &scopes[self.root], &[]) * &values[self.root]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Nnf, NnfError};
+    use num::BigInt;
+    use std::collections::BTreeSet;
+
+    /// The d-DNNF `(1 and 2) or (-1 and 3)` in the d4 format.
+    const D4: &str = "o 1 0\nt 2 0\n1 2 1 2 0\n1 2 -1 3 0\n";
+
+    #[test]
+    fn count() {
+        let nnf: Nnf = D4.parse().unwrap();
+
+        // Each child leaves one variable free.
+        assert_eq!(nnf.count(3), BigInt::from(4));
+        assert_eq!(nnf.count(5), BigInt::from(16));
+        assert_eq!(nnf.count(0), BigInt::from(4));
+
+        let constants = |input: &str| input.parse::<Nnf>().unwrap().count(2);
+        assert_eq!(constants("t 1 0\n"), BigInt::from(4));
+        assert_eq!(constants("f 1 0\n"), BigInt::ZERO);
+        assert_eq!(constants("nnf 1 0 3\nA 0\n"), BigInt::from(8));
+    }
+
+    #[test]
+    fn weighted_count() {
+        let nnf: Nnf = D4.parse().unwrap();
+
+        // Positive literals weigh their variable, negative ones weigh 1.
+        let weight = |literal: i64| BigInt::from(if literal > 0 { literal } else { 1 });
+
+        // 1 * 2 * (3 + 1) + 1 * 3 * (2 + 1)
+        assert_eq!(nnf.weighted_count(3, weight), BigInt::from(17));
+    }
+
+    #[test]
+    fn chain() {
+        // Deciding on each variable in turn, stopping at the first positive one, leaves all later
+        // variables free.
+        let variables = 300;
+        let mut input = String::new();
+
+        for variable in 1..=variables {
+            input += &format!("o {variable} 0\n");
+        }
+
+        input += &format!("t {} 0\n", variables + 1);
+
+        for variable in 1..=variables {
+            let next = if variable < variables {
+                variable + 1
+            } else {
+                variables + 1
+            };
+
+            input += &format!("{variable} {} {variable} 0\n", variables + 1);
+            input += &format!("{variable} {next} -{variable} 0\n");
+        }
+
+        let nnf: Nnf = input.parse().unwrap();
+        nnf.validate().unwrap();
+
+        assert_eq!(nnf.count(variables), BigInt::from(1) << variables);
+        assert_eq!(nnf.count(variables + 2), BigInt::from(1) << (variables + 2));
+
+        // Positive literals weigh 2 and negative ones 1, so each model weighs 2 per positive one.
+        let weight = |literal: i64| BigInt::from(if literal > 0 { 2 } else { 1 });
+        assert_eq!(
+            nnf.weighted_count(variables, weight),
+            BigInt::from(3).pow(variables as u32)
+        );
+
+        let projection = (1..=variables).collect();
+        assert_eq!(
+            nnf.projected_count(&projection).unwrap(),
+            BigInt::from(1) << variables
+        );
+    }
+
+    #[test]
+    fn projected_count() {
+        let nnf: Nnf = D4.parse().unwrap();
+
+        assert_eq!(
+            nnf.projected_count(&BTreeSet::from([1])).unwrap(),
+            BigInt::from(2)
+        );
+        assert_eq!(
+            nnf.projected_count(&BTreeSet::from([1, 4])).unwrap(),
+            BigInt::from(4)
+        );
+
+        // Forgetting the decision variable overlaps both children.
+        assert!(matches!(
+            nnf.projected_count(&BTreeSet::from([2, 3])),
+            Err(NnfError::NotDeterministic { node: 1 })
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+/// Reasons for failing to read an NNF or for it not being a d-DNNF.
+#[derive(Debug)]
+pub enum NnfError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// A line is malformed, the line number being `1`-indexed.
+    Syntax { line: usize, message: String },
+    /// A node references another node not defined.
+    Node(usize),
+    /// The nodes contain a cycle.
+    Cycle,
+    /// The NNF contains no nodes.
+    Empty,
+    /// Children of a conjunction or an edge and its literals share a variable.
+    NotDecomposable { node: usize, variable: u64 },
+    /// Two children of a disjunction have a common model.
+    NotDeterministic { node: usize },
+}
+
+impl Display for NnfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NnfError::Io(error) => write!(f, "failed to read NNF: {error}"),
+            NnfError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            NnfError::Node(node) => write!(f, "undefined node {node}"),
+            NnfError::Cycle => write!(f, "cyclic NNF"),
+            NnfError::Empty => write!(f, "NNF without nodes"),
+            NnfError::NotDecomposable { node, variable } => {
+                write!(f, "node {node} is not decomposable on variable {variable}")
+            }
+            NnfError::NotDeterministic { node } => write!(f, "node {node} is not deterministic"),
+        }
+    }
+}
+
+impl Error for NnfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NnfError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
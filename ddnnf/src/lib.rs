@@ -0,0 +1,15 @@
+//! This crate reads circuits in negation normal form as written by the knowledge compilers c2d
+//! and d4.
+//!
+//! Circuits can be checked for being decomposable and deterministic, making them d-DNNFs.
+//! Their models can then be counted, also weighted or projected onto some of their variables.
+
+mod check;
+mod compose;
+mod count;
+mod error;
+mod nnf;
+
+pub use compose::Composer;
+pub use error::NnfError;
+pub use nnf::{Metrics, Nnf};
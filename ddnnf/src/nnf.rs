@@ -0,0 +1,544 @@
+use crate::NnfError;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// A circuit in negation normal form as written by c2d or d4.
+#[derive(Debug, Clone)]
+pub struct Nnf {
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) root: usize,
+    /// All nodes ordered such that parents precede their children.
+    pub(crate) order: Vec<usize>,
+    /// The id of each node as given in the file, used for reporting errors.
+    pub(crate) ids: Vec<usize>,
+    pub(crate) num_vars: u64,
+}
+
+/// A node of an NNF.
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    pub(crate) kind: Kind,
+    pub(crate) children: Vec<Edge>,
+}
+
+/// The kinds of nodes in an NNF.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Kind {
+    And,
+    /// A disjunction, deciding on a variable if its children differ in its value.
+    Or {
+        decision: Option<u64>,
+    },
+    True,
+    False,
+    Literal(i64),
+}
+
+/// An edge to a child node, conjoined with literals in the d4 format.
+#[derive(Debug, Clone)]
+pub(crate) struct Edge {
+    pub(crate) child: usize,
+    pub(crate) literals: Vec<i64>,
+}
+
+/// Size metrics of an NNF.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// The amount of nodes, not counting literals on edges.
+    pub nodes: usize,
+    /// The amount of edges.
+    pub edges: usize,
+    /// The amount of edges on the longest path from the root.
+    pub depth: usize,
+    /// The amount of disjunctions deciding on a variable.
+    pub decisions: usize,
+}
+
+impl Nnf {
+    /// Reads an NNF in the c2d or d4 format from a file.
+    pub fn read(path: &Path) -> Result<Self, NnfError> {
+        fs::read_to_string(path).map_err(NnfError::Io)?.parse()
+    }
+
+    /// The amount of variables as declared by the c2d header or the highest variable otherwise.
+    pub fn num_vars(&self) -> u64 {
+        self.num_vars
+    }
+
+    /// Calculates the size metrics.
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            nodes: self.nodes.len(),
+            edges: self.nodes.iter().map(|node| node.children.len()).sum(),
+            depth: self.depth(),
+            decisions: self
+                .nodes
+                .iter()
+                .filter(|node| matches!(node.kind, Kind::Or { decision: Some(_) }))
+                .count(),
+        }
+    }
+
+    /// The amount of edges on the longest path from the root.
+    fn depth(&self) -> usize {
+        let mut depths = vec![0; self.nodes.len()];
+
+        // Children are considered before their parents.
+        for &node in self.order.iter().rev() {
+            depths[node] = self.nodes[node]
+                .children
+                .iter()
+                .map(|edge| depths[edge.child] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        depths[self.root]
+    }
+
+    /// The variables occurring below each node, including those of literals on edges.
+    pub(crate) fn variables(&self) -> Vec<BTreeSet<u64>> {
+        let mut variables = vec![BTreeSet::new(); self.nodes.len()];
+
+        // Children are considered before their parents.
+        for &index in self.order.iter().rev() {
+            let node = &self.nodes[index];
+
+            let mut scope = match node.kind {
+                Kind::Literal(literal) => BTreeSet::from([literal.unsigned_abs()]),
+                _ => BTreeSet::new(),
+            };
+
+            for edge in &node.children {
+                scope.extend(&variables[edge.child]);
+                scope.extend(edge.literals.iter().map(|literal| literal.unsigned_abs()));
+            }
+
+            variables[index] = scope;
+        }
+
+        variables
+    }
+
+    /// Creates an NNF after checking that its nodes are acyclic.
+    ///
+    /// Without a declared amount of variables, the highest variable is taken.
+    pub(crate) fn new(
+        nodes: Vec<Node>,
+        root: usize,
+        ids: Vec<usize>,
+        num_vars: Option<u64>,
+    ) -> Result<Self, NnfError> {
+        let mut parents = vec![0; nodes.len()];
+
+        nodes
+            .iter()
+            .flat_map(|node| &node.children)
+            .for_each(|edge| parents[edge.child] += 1);
+
+        // Repeatedly take the nodes whose parents were all taken already.
+        let mut order: Vec<usize> = (0..nodes.len())
+            .filter(|&node| parents[node] == 0)
+            .collect();
+        let mut next = 0;
+
+        while let Some(&node) = order.get(next) {
+            next += 1;
+
+            for edge in &nodes[node].children {
+                parents[edge.child] -= 1;
+
+                if parents[edge.child] == 0 {
+                    order.push(edge.child);
+                }
+            }
+        }
+
+        if order.len() != nodes.len() {
+            return Err(NnfError::Cycle);
+        }
+
+        let highest = nodes
+            .iter()
+            .flat_map(|node| {
+                let literal = match node.kind {
+                    Kind::Literal(literal) => Some(literal),
+                    _ => None,
+                };
+
+                node.children
+                    .iter()
+                    .flat_map(|edge| &edge.literals)
+                    .copied()
+                    .chain(literal)
+            })
+            .map(i64::unsigned_abs)
+            .max()
+            .unwrap_or(0);
+
+        Ok(Self {
+            nodes,
+            root,
+            order,
+            ids,
+            num_vars: num_vars.unwrap_or(highest).max(highest),
+        })
+    }
+
+    /// Parses the c2d format, starting with an `nnf` header and listing children before parents.
+    fn parse_c2d(lines: &[(usize, Vec<&str>)]) -> Result<Self, NnfError> {
+        let mut nodes = Vec::with_capacity(lines.len());
+
+        // The header declares the amount of nodes, edges and variables.
+        let (line, header) = &lines[0];
+        let num_vars = match header[..] {
+            [_, _, _, num_vars] => num_vars.parse().map_err(|_| NnfError::Syntax {
+                line: *line,
+                message: String::from("invalid amount of variables"),
+            })?,
+            _ => {
+                return Err(NnfError::Syntax {
+                    line: *line,
+                    message: String::from("invalid header"),
+                })
+            }
+        };
+
+        for (line, tokens) in &lines[1..] {
+            let syntax = |message: &str| NnfError::Syntax {
+                line: *line,
+                message: message.to_string(),
+            };
+
+            let numbers = tokens[1..]
+                .iter()
+                .map(|token| token.parse::<i64>())
+                .collect::<Result<Vec<i64>, _>>()
+                .map_err(|_| syntax("invalid number"))?;
+
+            // Children are given by their count followed by their indices.
+            let children = |offset: usize| -> Result<Vec<Edge>, NnfError> {
+                let count = *numbers.get(offset).ok_or_else(|| syntax("missing count"))?;
+                let children = &numbers[offset + 1..];
+
+                if children.len() as i64 != count {
+                    return Err(syntax("wrong amount of children"));
+                }
+
+                children
+                    .iter()
+                    .map(|&child| match usize::try_from(child) {
+                        Ok(child) if child < nodes.len() => Ok(Edge {
+                            child,
+                            literals: Vec::new(),
+                        }),
+                        _ => Err(NnfError::Node(child as usize)),
+                    })
+                    .collect()
+            };
+
+            let node = match tokens[0] {
+                "L" => match numbers[..] {
+                    [literal] if literal != 0 => Node {
+                        kind: Kind::Literal(literal),
+                        children: Vec::new(),
+                    },
+                    _ => return Err(syntax("invalid literal")),
+                },
+                "A" => {
+                    let children = children(0)?;
+
+                    Node {
+                        kind: if children.is_empty() {
+                            Kind::True
+                        } else {
+                            Kind::And
+                        },
+                        children,
+                    }
+                }
+                "O" => {
+                    let decision = *numbers.first().ok_or_else(|| syntax("missing decision"))?;
+                    let children = children(1)?;
+
+                    Node {
+                        kind: if children.is_empty() {
+                            Kind::False
+                        } else {
+                            Kind::Or {
+                                decision: (decision != 0).then_some(decision.unsigned_abs()),
+                            }
+                        },
+                        children,
+                    }
+                }
+                _ => return Err(syntax("unknown node type")),
+            };
+
+            nodes.push(node);
+        }
+
+        // The root is the last node.
+        let root = nodes.len().checked_sub(1).ok_or(NnfError::Empty)?;
+
+        // Nodes are referenced by their index.
+        let ids = (0..nodes.len()).collect();
+
+        Self::new(nodes, root, ids, Some(num_vars))
+    }
+
+    /// Parses the d4 format, declaring nodes as `<type> <id> 0` and edges as
+    /// `<parent> <child> <literals> 0`.
+    fn parse_d4(lines: &[(usize, Vec<&str>)]) -> Result<Self, NnfError> {
+        // Node ids are arbitrary, so they are mapped to indices in order of declaration.
+        let mut indices = HashMap::new();
+        let mut ids = Vec::new();
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for (line, tokens) in lines {
+            let syntax = |message: &str| NnfError::Syntax {
+                line: *line,
+                message: message.to_string(),
+            };
+
+            if tokens.last() != Some(&"0") {
+                return Err(syntax("missing terminating 0"));
+            }
+
+            let kind = match tokens[0] {
+                "a" => Some(Kind::And),
+                "o" => Some(Kind::Or { decision: None }),
+                "t" => Some(Kind::True),
+                "f" => Some(Kind::False),
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                let id: usize = match tokens[..] {
+                    [_, id, _] => id.parse().map_err(|_| syntax("invalid node id"))?,
+                    _ => return Err(syntax("invalid node declaration")),
+                };
+
+                indices.insert(id, nodes.len());
+                ids.push(id);
+                nodes.push(Node {
+                    kind,
+                    children: Vec::new(),
+                });
+
+                continue;
+            }
+
+            let numbers = tokens[..tokens.len() - 1]
+                .iter()
+                .map(|token| token.parse::<i64>())
+                .collect::<Result<Vec<i64>, _>>()
+                .map_err(|_| syntax("invalid number"))?;
+
+            match numbers[..] {
+                [parent, child, ref literals @ ..]
+                    if parent > 0 && child > 0 && !literals.contains(&0) =>
+                {
+                    edges.push((parent as usize, child as usize, literals.to_vec()))
+                }
+                _ => return Err(syntax("invalid edge")),
+            }
+        }
+
+        // Edges may precede the declarations of their nodes.
+        for (parent, child, literals) in edges {
+            let parent = *indices.get(&parent).ok_or(NnfError::Node(parent))?;
+            let child = *indices.get(&child).ok_or(NnfError::Node(child))?;
+
+            nodes[parent].children.push(Edge { child, literals });
+        }
+
+        // A disjunction decides on a variable occurring positively and negatively on its edges.
+        for node in &mut nodes {
+            if let Kind::Or { decision } = &mut node.kind {
+                *decision = node.children.iter().find_map(|edge| {
+                    edge.literals.iter().find_map(|&literal| {
+                        node.children
+                            .iter()
+                            .any(|other| other.literals.contains(&-literal))
+                            .then_some(literal.unsigned_abs())
+                    })
+                });
+            }
+        }
+
+        // The root is the first node declared.
+        if nodes.is_empty() {
+            return Err(NnfError::Empty);
+        }
+
+        Self::new(nodes, 0, ids, None)
+    }
+}
+
+impl FromStr for Nnf {
+    type Err = NnfError;
+
+    /// Parses an NNF, detecting the c2d format by its header and assuming the d4 format otherwise.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Keep the line numbers of all lines not being empty or comments.
+        let lines: Vec<(usize, Vec<&str>)> = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.split_whitespace().collect::<Vec<&str>>()))
+            .filter(|(_, tokens)| tokens.first().is_some_and(|&token| token != "c"))
+            .collect();
+
+        match lines.first() {
+            Some((_, tokens)) if tokens[0] == "nnf" => Self::parse_c2d(&lines),
+            _ => Self::parse_d4(&lines),
+        }
+    }
+}
+
+impl Display for Nnf {
+    /// Writes the NNF in the d4 format, the root being node `1`.
+    ///
+    /// Literal nodes become conjunctions with an edge to an additional true node.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        // The root is declared first, followed by all other nodes in their order.
+        let declarations: Vec<usize> = [self.root]
+            .into_iter()
+            .chain((0..self.nodes.len()).filter(|&index| index != self.root))
+            .collect();
+
+        let mut ids = vec![0; self.nodes.len()];
+        declarations
+            .iter()
+            .enumerate()
+            .for_each(|(position, &index)| ids[index] = position + 1);
+
+        for &index in &declarations {
+            let kind = match self.nodes[index].kind {
+                Kind::And | Kind::Literal(_) => "a",
+                Kind::Or { .. } => "o",
+                Kind::True => "t",
+                Kind::False => "f",
+            };
+
+            writeln!(f, "{kind} {} 0", ids[index])?;
+        }
+
+        let literal = self.nodes.len() + 1;
+
+        if self
+            .nodes
+            .iter()
+            .any(|node| matches!(node.kind, Kind::Literal(_)))
+        {
+            writeln!(f, "t {literal} 0")?;
+        }
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let Kind::Literal(value) = node.kind {
+                writeln!(f, "{} {literal} {value} 0", ids[index])?;
+            }
+
+            for edge in &node.children {
+                write!(f, "{} {}", ids[index], ids[edge.child])?;
+
+                for literal in &edge.literals {
+                    write!(f, " {literal}")?;
+                }
+
+                writeln!(f, " 0")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Metrics, Nnf, NnfError};
+
+    /// The d-DNNF `(1 and 2) or (-1 and 3)` in the c2d format.
+    const C2D: &str = "nnf 7 6 3
+L 1
+L 2
+L -1
+L 3
+A 2 0 1
+A 2 2 3
+O 1 2 4 5
+";
+
+    /// The same d-DNNF in the d4 format.
+    const D4: &str = "o 1 0
+a 2 0
+t 3 0
+1 3 1 2 0
+1 3 -1 3 0
+";
+
+    #[test]
+    fn c2d() {
+        let nnf: Nnf = C2D.parse().unwrap();
+
+        assert_eq!(
+            nnf.metrics(),
+            Metrics {
+                nodes: 7,
+                edges: 6,
+                depth: 2,
+                decisions: 1
+            }
+        );
+    }
+
+    #[test]
+    fn d4() {
+        let nnf: Nnf = D4.parse().unwrap();
+
+        // The unused conjunction still counts as a node.
+        assert_eq!(
+            nnf.metrics(),
+            Metrics {
+                nodes: 3,
+                edges: 2,
+                depth: 1,
+                decisions: 1
+            }
+        );
+    }
+
+    #[test]
+    fn constants() {
+        let metrics = |input: &str| input.parse::<Nnf>().unwrap().metrics();
+
+        assert_eq!(metrics("nnf 1 0 0\nO 0 0\n").nodes, 1);
+        assert_eq!(metrics("nnf 1 0 0\nA 0\n").depth, 0);
+        assert_eq!(metrics("f 1 0\n").decisions, 0);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!("".parse::<Nnf>(), Err(NnfError::Empty)));
+        assert!(matches!(
+            "nnf 2 1 1\nA 1 1\nL 1\n".parse::<Nnf>(),
+            Err(NnfError::Node(1))
+        ));
+        assert!(matches!(
+            "o 1 0\n1 2 0\n".parse::<Nnf>(),
+            Err(NnfError::Node(2))
+        ));
+        assert!(matches!(
+            "a 1 0\na 2 0\n1 2 0\n2 1 0\n".parse::<Nnf>(),
+            Err(NnfError::Cycle)
+        ));
+        assert!(matches!(
+            "o 1 0\nx 2\n".parse::<Nnf>(),
+            Err(NnfError::Syntax { line: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+//! Imitates d4 for preprocessing and compiling a CNF.
+//!
+//! Preprocessing copies the input unchanged, compiling counts the models by brute force.
+//! The dumped d-DNNF is the disjunction of all models.
+//! Unsatisfiable CNFs are reported using d4's wording, followed by a comment as in d4's statistics.
+
+use clap::Parser;
+use fake_tools::{count_models, ddnnf};
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// CNF DIMACS input file
+    #[arg(long)]
+    input: PathBuf,
+
+    /// Only `ddnnf-compiler` is supported
+    #[arg(long)]
+    method: String,
+
+    /// Whether to only preprocess the input
+    #[arg(long)]
+    only_preproc: Option<u8>,
+
+    /// Where to write the preprocessed input to
+    #[arg(long)]
+    dump_preproc: Option<PathBuf>,
+
+    /// Ignored
+    #[arg(long)]
+    partitioning_heuristic: Option<String>,
+
+    /// Where to write the compiled d-DNNF to
+    #[arg(long)]
+    dump_file: Option<PathBuf>,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    assert_eq!(args.method, "ddnnf-compiler", "Unsupported method.");
+
+    if args.only_preproc == Some(1) {
+        let output = args
+            .dump_preproc
+            .expect("Preprocessing requires `--dump-preproc`.");
+
+        fs::copy(&args.input, output).expect("Failed to write preprocessed input.");
+        return;
+    }
+
+    let input = fs::read_to_string(&args.input).expect("Failed to read input file.");
+
+    if let Some(path) = args.dump_file {
+        let cnf = input.parse().expect("Failed to parse DIMACS.");
+        fs::write(path, ddnnf(&cnf)).expect("Failed to write d-DNNF.");
+    }
+
+    println!("c [fake d4] counting models by brute force");
+
+    match count_models(&input) {
+        0 => println!("s UNSATISFIABLE"),
+        count => println!("s {count}"),
+    }
+
+    println!("c [fake d4] done");
+}
@@ -0,0 +1,27 @@
+//! Imitates `shmetis`, `hmetis` and `khmetis` partitioning a hypergraph in the hMETIS format.
+//!
+//! Usage: `fake_hmetis <graph> <blocks> <UBfactor> [options]`, where the options are those of
+//! `hmetis` (6) or `khmetis` (5) and are not used. The partition does not depend on a seed.
+
+use fake_tools::{partition, read_hmetis, write_partition};
+use std::env;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    assert!(
+        [3, 8, 9].contains(&args.len()),
+        "Expected the arguments of shmetis, hmetis or khmetis."
+    );
+
+    let blocks: usize = args[1].parse().expect("Invalid amount of blocks.");
+    let ubfactor: u32 = args[2].parse().expect("Invalid UBfactor.");
+    assert!(ubfactor >= 1, "UBfactor must be at least 1.");
+
+    let graph = read_hmetis(args[0].as_ref());
+
+    write_partition(
+        format!("{}.part.{blocks}", args[0]).as_ref(),
+        &partition(&graph, blocks, ubfactor as f32 / 100.0, 0),
+    );
+}
@@ -0,0 +1,55 @@
+//! Imitates KaHyPar partitioning a hypergraph in the hMETIS format.
+
+use clap::Parser;
+use fake_tools::{partition, read_hmetis, write_partition};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+struct Args {
+    #[arg(long)]
+    hypergraph: PathBuf,
+
+    #[arg(long)]
+    blocks: usize,
+
+    #[arg(long)]
+    mode: String,
+
+    #[arg(long)]
+    objective: String,
+
+    /// Kept as given since it is part of the output file name
+    #[arg(long)]
+    epsilon: String,
+
+    #[arg(long)]
+    seed: u64,
+
+    #[arg(long)]
+    preset: PathBuf,
+
+    #[arg(long)]
+    write_partition: u8,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let graph = read_hmetis(&args.hypergraph);
+    let epsilon = args.epsilon.parse().expect("Invalid epsilon.");
+
+    let output = format!(
+        "{}.part{}.epsilon{}.seed{}.KaHyPar",
+        args.hypergraph.display(),
+        args.blocks,
+        args.epsilon,
+        args.seed
+    );
+
+    if args.write_partition == 1 {
+        write_partition(
+            output.as_ref(),
+            &partition(&graph, args.blocks, epsilon, args.seed),
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Imitates the PaToH command line driver partitioning a hypergraph in the PaToH format.
+//!
+//! Usage: `fake_patoh <graph> <blocks> [KEY=VALUE ...]`, only `IB` and `SD` are used.
+
+use fake_tools::{parse_patoh, partition, write_partition};
+use std::env;
+use std::fs;
+
+fn main() {
+    let mut args = env::args().skip(1);
+
+    let graph = args.next().expect("Missing hypergraph file.");
+    let blocks: usize = args
+        .next()
+        .expect("Missing amount of blocks.")
+        .parse()
+        .expect("Invalid amount of blocks.");
+
+    let mut epsilon = 0.03;
+    let mut seed = 0;
+
+    for parameter in args {
+        let (key, value) = parameter
+            .split_once('=')
+            .expect("Parameters must be given as KEY=VALUE.");
+
+        match key {
+            "IB" => epsilon = value.parse().expect("Invalid imbalance."),
+            "SD" => seed = value.parse().expect("Invalid seed."),
+            "UM" | "PQ" => {}
+            _ => panic!("Unsupported parameter {key}."),
+        }
+    }
+
+    let input = fs::read_to_string(&graph).expect("Failed to read input file.");
+    let graph_parsed = parse_patoh(&input);
+
+    write_partition(
+        format!("{graph}.part.{blocks}").as_ref(),
+        &partition(&graph_parsed, blocks, epsilon, seed),
+    );
+}
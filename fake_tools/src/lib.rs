@@ -0,0 +1,170 @@
+//! Stand-ins for the external tools used by `cnf_partitioner`.
+//!
+//! Each binary imitates the command line interface and output files of a tool closely enough for
+//! running the whole pipeline on small instances without the real tools being installed.
+//! Models are counted by brute force and partitions are created by the `hypergraph` crate.
+
+use hypergraph::{Hypergraph, Partition};
+use hypergraph_formats::cnf::Cnf;
+use hypergraph_formats::hmetis::FromStringHMETIS;
+use hypergraph_formats::{Format, Graph, Header, Net};
+use std::fs;
+use std::path::Path;
+
+/// The largest amount of variables to count the models of by brute force.
+pub const MAX_VARIABLES: usize = 24;
+
+/// Counts the models of a CNF in the DIMACS format by enumerating all assignments.
+pub fn count_models(input: &str) -> u128 {
+    let cnf: Cnf = input.parse().expect("Failed to parse DIMACS.");
+    models(&cnf).len() as u128
+}
+
+/// Writes a d-DNNF in the d4 format being the disjunction of all models of a CNF.
+///
+/// Each model is an edge from the root to the true node, labeled with all literals of the model.
+pub fn ddnnf(cnf: &Cnf) -> String {
+    let models = models(cnf);
+
+    if models.is_empty() {
+        return String::from("f 1 0\n");
+    }
+
+    let mut output = String::from("o 1 0\nt 2 0\n");
+
+    for model in models {
+        output.push_str("1 2");
+
+        for variable in 1..=cnf.num_vars() {
+            let sign = if model >> (variable - 1) & 1 == 1 {
+                ""
+            } else {
+                "-"
+            };
+            output.push_str(&format!(" {sign}{variable}"));
+        }
+
+        output.push_str(" 0\n");
+    }
+
+    output
+}
+
+/// Enumerates all models of a CNF, each encoded in the bits of a number.
+fn models(cnf: &Cnf) -> Vec<u64> {
+    let num_vars = cnf.num_vars() as usize;
+
+    assert!(
+        num_vars <= MAX_VARIABLES,
+        "Too many variables ({num_vars}) for counting by brute force."
+    );
+
+    // Represent each clause by its literals as variable index and whether it is positive.
+    let clauses: Vec<Vec<(usize, bool)>> = cnf
+        .clauses()
+        .iter()
+        .map(|clause| {
+            clause
+                .literals()
+                .map(|literal| (literal.variable() as usize - 1, literal.is_positive()))
+                .collect()
+        })
+        .collect();
+
+    // Each assignment is encoded in the bits of a number.
+    (0u64..1 << num_vars)
+        .filter(|assignment| {
+            clauses.iter().all(|clause| {
+                clause
+                    .iter()
+                    .any(|&(var, positive)| (assignment >> var & 1 == 1) == positive)
+            })
+        })
+        .collect()
+}
+
+/// Partitions a hypergraph randomly, respecting the given imbalance.
+pub fn partition(graph: &Graph, blocks: usize, epsilon: f32, seed: u64) -> Partition {
+    Hypergraph::from(graph).partition_random(blocks, epsilon, seed)
+}
+
+/// Writes a partition file containing the block of each vertex per line.
+pub fn write_partition(path: &Path, partition: &Partition) {
+    fs::write(path, partition.to_string()).expect("Failed to write partition file.");
+}
+
+/// Reads a hypergraph in the hMETIS format.
+///
+/// The parser requires a final line break, which the partitioners do not.
+pub fn read_hmetis(path: &Path) -> Graph {
+    let input = fs::read_to_string(path).expect("Failed to read input file.");
+    Graph::from_string_hmetis(&format!("{}\n", input.trim_end()))
+        .expect("Failed to parse hMETIS graph.")
+}
+
+/// Parses a hypergraph in the PaToH format.
+///
+/// Net weights are skipped, vertex weights are ignored.
+pub fn parse_patoh(input: &str) -> Graph {
+    let mut lines = input
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('%'));
+
+    let header: Vec<usize> = lines
+        .next()
+        .expect("Missing PaToH header.")
+        .split_whitespace()
+        .map(|value| value.parse().expect("Invalid PaToH header."))
+        .collect();
+
+    let (one_indexed, num_vertices, num_nets) = (header[0] == 1, header[1], header[2]);
+    let net_weights = matches!(header.get(4), Some(2 | 3));
+
+    let nets = lines
+        .take(num_nets)
+        .map(|line| {
+            let pins = line
+                .split_whitespace()
+                .skip(usize::from(net_weights))
+                .map(|pin| pin.parse().expect("Invalid PaToH pin."))
+                .collect();
+
+            Net(pins)
+        })
+        .collect();
+
+    Graph {
+        header: Header {
+            num_nets,
+            num_vertices,
+            format: Format::Unweighted,
+            one_indexed,
+        },
+        nets,
+        vertex_weights: Vec::new(),
+        net_weights: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ddnnf;
+    use ddnnf::Nnf;
+    use hypergraph_formats::cnf::Cnf;
+    use num::BigInt;
+
+    #[test]
+    fn disjunction_of_models() {
+        let cnf: Cnf = "p cnf 3 2\n1 2 0\n-1 3 0\n".parse().unwrap();
+        let nnf: Nnf = ddnnf(&cnf).parse().unwrap();
+        let metrics = nnf.metrics();
+
+        // One edge per model.
+        assert_eq!(metrics.edges, 4);
+        assert_eq!(metrics.decisions, 1);
+
+        assert!(nnf.validate().is_ok());
+        assert_eq!(nnf.count(cnf.num_vars()), BigInt::from(4));
+    }
+}
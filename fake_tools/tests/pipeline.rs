@@ -0,0 +1,768 @@
+use clap::Parser;
+use cnf_partitioner::batch::Deadline;
+use cnf_partitioner::d4::D4;
+use cnf_partitioner::output::Output;
+use cnf_partitioner::partitioner;
+use cnf_partitioner::pipeline::run_instance;
+use cnf_partitioner::process::Limits;
+use cnf_partitioner::Args;
+use std::collections::BTreeSet;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::{fs, io};
+use tempfile::TempDir;
+
+const CNF: &str = "p cnf 10 12
+1 2 0
+-1 3 0
+-2 -3 4 0
+4 5 0
+-5 6 0
+6 7 -4 0
+-7 8 0
+8 9 0
+-9 10 1 0
+-6 -10 0
+2 5 9 0
+-3 -8 7 0
+";
+
+const COUNT: &str = "34";
+
+/// Arguments for a single run of the BFS partitioner with two blocks.
+const BFS: [&str; 4] = ["-b", "2", "--native", "bfs"];
+
+/// The suffix of the output files of a run with [`BFS`].
+const BFS_RUN: &str = "BFS.None.2.0.1.0";
+
+/// Columns of optional steps along with their values in case the step is not requested.
+const DISABLED: [(&str, &str); 9] = [
+    ("nodes_original", "0"),
+    ("assignments_composed", "0"),
+    ("height_recursive", "0"),
+    ("refinement_moves", "0"),
+    ("width_dtree", "0"),
+    ("cubes", "0"),
+    ("time_estimated", "0"),
+    ("blocks_chosen", "0"),
+    ("blocks_trajectory", ""),
+];
+
+/// Runs the whole pipeline on the test CNF using the fake d4 and the given arguments.
+fn run(arguments: &[&str]) -> Output {
+    run_on(CNF, arguments)
+}
+
+/// Runs the whole pipeline on the test CNF using [`BFS`] followed by the given arguments.
+fn run_bfs(arguments: &[&str]) -> Output {
+    run(&[&BFS[..], arguments].concat())
+}
+
+/// The path of an output file of a run with [`BFS`] written to the given prefix.
+fn saved(directory: &TempDir, prefix: &str, suffix: &str) -> PathBuf {
+    directory.path().join(format!("{prefix}.{BFS_RUN}{suffix}"))
+}
+
+/// Runs the whole pipeline on a CNF using the fake d4 and the given arguments.
+fn run_on(cnf: &str, arguments: &[&str]) -> Output {
+    try_run_on(cnf, env!("CARGO_BIN_EXE_fake_d4"), arguments).expect("Failed to run the pipeline.")
+}
+
+/// Writes an executable shell script to imitate a tool.
+fn script(directory: &TempDir, content: &str) -> PathBuf {
+    let path = directory.path().join("script");
+    fs::write(&path, format!("#!/bin/sh\n{content}\n")).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+/// Runs the whole pipeline on a CNF using the given d4 and arguments.
+fn try_run_on(cnf: &str, d4: &str, arguments: &[&str]) -> io::Result<Output> {
+    let directory = TempDir::new().unwrap();
+    let input = directory.path().join("test.cnf");
+    fs::write(&input, cnf).unwrap();
+
+    let args = Args::parse_from(
+        ["cnf_partitioner", "--d4-path", d4]
+            .iter()
+            .chain(arguments)
+            .chain([&input.to_str().unwrap()]),
+    );
+
+    let d4 = D4::new(args.d4_path.clone(), Limits::default());
+    let partitioners = partitioner::instantiate(&args);
+    let mut output = Output::new();
+
+    run_instance(
+        &args,
+        &d4,
+        &partitioners,
+        &input,
+        Deadline::new(None),
+        &mut output,
+    )?;
+
+    Ok(output)
+}
+
+/// Extracts the column with the given name from each row of the CSV output.
+fn column(output: &Output, name: &str) -> Vec<String> {
+    let index = cnf_partitioner::output::Run::csv_header()
+        .split(',')
+        .position(|column| column == name)
+        .expect("Unknown column.");
+
+    output
+        .csv()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split(',').nth(index).unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn external_partitioners() {
+    let kahypar = env!("CARGO_BIN_EXE_fake_kahypar");
+    let mtkahypar = env!("CARGO_BIN_EXE_fake_mtkahypar");
+    let patoh = env!("CARGO_BIN_EXE_fake_patoh");
+    let hmetis = env!("CARGO_BIN_EXE_fake_hmetis");
+
+    // The blocks, the tool and its path, the hMETIS variant if any, and the expected name.
+    let partitioners = [
+        ("2", "kahypar", kahypar, None, "KaHyPar"),
+        ("3", "mtkahypar", mtkahypar, None, "Mt-KaHyPar"),
+        ("2", "patoh", patoh, None, "PaToH"),
+        ("2", "hmetis", hmetis, Some("shmetis"), "shMETIS"),
+        ("2", "hmetis", hmetis, Some("hmetis"), "hMETIS"),
+        ("2", "hmetis", hmetis, Some("khmetis"), "khMETIS"),
+    ];
+
+    for (blocks, tool, path, variant, name) in partitioners {
+        let enable = format!("--{tool}-enable");
+        let path_flag = format!("--{tool}-path");
+        let mut arguments = vec!["-b", blocks, &enable, &path_flag, path];
+        arguments.extend(
+            variant
+                .iter()
+                .flat_map(|variant| ["--hmetis-variant", variant]),
+        );
+
+        let output = run(&arguments);
+
+        assert_eq!(column(&output, "partitioner"), [name]);
+        assert_eq!(column(&output, "count_original"), [COUNT]);
+    }
+}
+
+#[test]
+fn partitioner_timeout() {
+    // A partitioner never finishing within the time limit.
+    let directory = TempDir::new().unwrap();
+    let path = script(&directory, "sleep 10");
+
+    let output = run(&[
+        "-b",
+        "2",
+        "--partitioner-timeout",
+        "1",
+        "--kahypar-enable",
+        "--kahypar-path",
+        path.to_str().unwrap(),
+        "--native",
+        "bfs",
+    ]);
+
+    // The timeout is recorded while the other partitioner still runs.
+    assert_eq!(column(&output, "partitioner"), ["KaHyPar", "BFS"]);
+    assert_eq!(column(&output, "status"), ["timeout", "finished"]);
+    assert_eq!(
+        column(&output, "time_partitioning")[0],
+        u128::MAX.to_string()
+    );
+}
+
+#[test]
+fn invalid_blocks() {
+    // A partitioner assigning all vertices to a block beyond the requested ones.
+    let directory = TempDir::new().unwrap();
+    let path = script(
+        &directory,
+        r#"while [ $# -gt 0 ]; do
+    case $1 in
+        --hypergraph) graph=$2 ;;
+        --blocks) blocks=$2 ;;
+        --epsilon) epsilon=$2 ;;
+        --seed) seed=$2 ;;
+    esac
+    shift
+done
+vertices=$(head -n 1 "$graph" | cut -d ' ' -f 2)
+yes 7 | head -n "$vertices" > "$graph.part$blocks.epsilon$epsilon.seed$seed.KaHyPar""#,
+    );
+
+    for refine in [false, true] {
+        let mut arguments = vec![
+            "-b",
+            "2",
+            "--kahypar-enable",
+            "--kahypar-path",
+            path.to_str().unwrap(),
+        ];
+
+        if refine {
+            arguments.push("--refine");
+        }
+
+        let error = try_run_on(CNF, env!("CARGO_BIN_EXE_fake_d4"), &arguments).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
+
+#[test]
+fn optional_steps() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("ddnnf");
+
+    // Without requesting them, the columns of optional steps keep their defaults.
+    let output = run_bfs(&[]);
+
+    assert_eq!(column(&output, "status"), ["finished"]);
+    assert_eq!(
+        column(&output, "cut_before_refinement"),
+        column(&output, "cut_after_refinement")
+    );
+
+    for (name, value) in DISABLED {
+        assert_eq!(column(&output, name), [value], "{name}");
+    }
+
+    // Requesting a step fills its column.
+    let steps: [(&[&str], &str); 5] = [
+        (
+            &["--dump-ddnnf", prefix.to_str().unwrap()],
+            "nodes_original",
+        ),
+        (&["--compose", "8"], "assignments_composed"),
+        (&["--recursive"], "height_recursive"),
+        (&["--dtree"], "width_dtree"),
+        (&["--count-cubes", "100000"], "cubes"),
+    ];
+
+    for (arguments, name) in steps {
+        let (_, disabled) = DISABLED.iter().find(|(column, _)| *column == name).unwrap();
+        assert_ne!(column(&run_bfs(arguments), name), [*disabled], "{name}");
+    }
+}
+
+#[test]
+fn native() {
+    let output = run(&["-b", "2", "--native", "bfs,dfs,random"]);
+
+    assert_eq!(column(&output, "partitioner"), ["BFS", "DFS", "Random"]);
+    assert_eq!(column(&output, "count_original"), [COUNT; 3]);
+}
+
+#[test]
+fn separator() {
+    let output = run(&[
+        "--blocks-start",
+        "2",
+        "--blocks-end",
+        "3",
+        "--native",
+        "bfs,separator",
+    ]);
+
+    // Both approaches are reported in the same format, the counts being checked while running.
+    assert_eq!(
+        column(&output, "partitioner"),
+        ["BFS", "Separator"].repeat(2)
+    );
+    assert_eq!(column(&output, "count_original"), [COUNT; 4]);
+    assert!(column(&output, "cut_size")
+        .iter()
+        .all(|cut| cut.parse::<usize>().is_ok()));
+}
+
+#[test]
+fn configurations() {
+    let output = run(&[
+        "--blocks-start",
+        "2",
+        "--blocks-end",
+        "3",
+        "-e",
+        "0.05,0.2",
+        "--repetitions",
+        "2",
+        "--heuristics",
+        "moms",
+        "--kahypar-enable",
+        "--kahypar-path",
+        env!("CARGO_BIN_EXE_fake_kahypar"),
+    ]);
+
+    // Blocks, epsilons, seeds and heuristics (including none).
+    assert_eq!(output.len(), 2 * 2 * 2 * 2);
+    assert_eq!(column(&output, "seed"), ["0", "0", "1", "1"].repeat(4));
+}
+
+#[test]
+fn free_variables() {
+    // Variables `11` and `12` do not occur in any clause.
+    let cnf = CNF.replace("p cnf 10 12", "p cnf 12 12");
+    let output = run_on(&cnf, &["-b", "2", "--native", "bfs"]);
+
+    assert_eq!(column(&output, "count_original"), ["136"]);
+    assert_eq!(column(&output, "free_variables"), ["2"]);
+}
+
+#[test]
+fn save_split() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("split");
+
+    run_bfs(&["--save-split", prefix.to_str().unwrap()]);
+
+    for part in 0..2 {
+        let path = |extension: &str| saved(&directory, "split", &format!(".{part}.{extension}"));
+
+        let cnf = fs::read_to_string(path("cnf")).unwrap();
+        let map = fs::read_to_string(path("map")).unwrap();
+
+        // Each variable of the compacted part is mapped to an original one.
+        let num_vars: usize = cnf.split_whitespace().nth(2).unwrap().parse().unwrap();
+        assert_eq!(map.lines().count(), num_vars);
+        assert!(map
+            .lines()
+            .enumerate()
+            .all(|(index, line)| line.starts_with(&format!("{} ", index + 1))));
+    }
+}
+
+#[test]
+fn save_order() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("order");
+
+    let output = run_bfs(&[
+        "--save-order",
+        prefix.to_str().unwrap(),
+        "--save-partitions",
+        prefix.to_str().unwrap(),
+    ]);
+
+    let order: Vec<u64> = fs::read_to_string(saved(&directory, "order", ".order"))
+        .unwrap()
+        .lines()
+        .map(|line| line.parse().unwrap())
+        .collect();
+
+    // Each variable occurs exactly once.
+    let mut sorted = order.clone();
+    sorted.sort();
+    assert_eq!(sorted, (1..=10).collect::<Vec<u64>>());
+
+    // The variables of each block as given by the partition.
+    let partition = fs::read_to_string(saved(&directory, "order", "")).unwrap();
+    let mut blocks = vec![BTreeSet::new(); 2];
+
+    for (clause, block) in CNF.lines().skip(1).zip(partition.lines()) {
+        let block: usize = block.parse().unwrap();
+
+        clause
+            .split_whitespace()
+            .map(|literal| literal.parse::<i64>().unwrap().unsigned_abs())
+            .filter(|&variable| variable != 0)
+            .for_each(|variable| {
+                blocks[block].insert(variable);
+            });
+    }
+
+    // The cut variables come first, followed by those of the first block.
+    let cut: BTreeSet<u64> = blocks[0].intersection(&blocks[1]).copied().collect();
+    let first = blocks[0].len();
+
+    assert_eq!(column(&output, "cut_size"), [cut.len().to_string()]);
+    assert!(order[..cut.len()]
+        .iter()
+        .all(|variable| cut.contains(variable)));
+    assert!(order[cut.len()..first]
+        .iter()
+        .all(|variable| blocks[0].contains(variable) && !cut.contains(variable)));
+}
+
+#[test]
+fn save_cut() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("cut");
+
+    // Name every variable as feature models do.
+    let names: String = (1..=10)
+        .map(|variable| format!("c {variable} F{variable}\n"))
+        .collect();
+    let cnf = format!("{names}{CNF}");
+
+    for method in ["d4", "native", "none"] {
+        let output = run_on(
+            &cnf,
+            &[
+                "-b",
+                "2",
+                "--native",
+                "bfs",
+                "--preprocessing",
+                method,
+                "--save-cut",
+                prefix.to_str().unwrap(),
+            ],
+        );
+
+        let cut = fs::read_to_string(saved(&directory, "cut", ".cut")).unwrap();
+        assert_eq!(
+            column(&output, "cut_size"),
+            [cut.lines().count().to_string()]
+        );
+
+        // Each assigned cut variable is followed by its name.
+        for line in cut.lines() {
+            let (literal, name) = line.split_once(' ').unwrap();
+            let literal: i64 = literal.parse().unwrap();
+
+            assert!(name.starts_with('F'));
+
+            if method != "native" {
+                assert_eq!(name, format!("F{}", literal.unsigned_abs()));
+            }
+        }
+    }
+}
+
+#[test]
+fn assignment_strategies() {
+    for strategy in ["first", "random", "sample", "max-count"] {
+        let output = run_bfs(&[
+            "--assignment-strategy",
+            strategy,
+            "--assignment-samples",
+            "3",
+        ]);
+
+        // The assignment conditioned on covers the cut, the counts being checked while running.
+        let cut_size: usize = column(&output, "cut_size")[0].parse().unwrap();
+        let assignment = &column(&output, "assignment")[0];
+
+        assert_eq!(column(&output, "assignment_strategy"), [strategy]);
+        assert_eq!(assignment.split(';').count(), cut_size);
+
+        let samples: usize = column(&output, "samples")[0].parse().unwrap();
+        let times: Vec<u128> = ["min", "median", "max"]
+            .iter()
+            .map(|statistic| {
+                column(&output, &format!("time_split_{statistic}"))[0]
+                    .parse()
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(times.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        if strategy == "sample" {
+            assert!((1..=3).contains(&samples));
+        } else {
+            assert_eq!(samples, 1);
+            assert_eq!(
+                column(&output, "time_split_min"),
+                column(&output, "time_sum")
+            );
+        }
+    }
+}
+
+#[test]
+fn count_cubes() {
+    let output = run_bfs(&["--count-cubes", "100000"]);
+
+    // The cut of the test CNF is small enough to enumerate its satisfying assignments.
+    let cut_size: u32 = column(&output, "cut_size")[0].parse().unwrap();
+    let cubes: u128 = column(&output, "cubes")[0].parse().unwrap();
+    let time_sum: u128 = column(&output, "time_sum")[0].parse().unwrap();
+
+    assert_eq!(column(&output, "cubes_exact"), ["true"]);
+    assert!((1..=1 << cut_size).contains(&cubes));
+    assert_eq!(
+        column(&output, "time_estimated"),
+        [(cubes * time_sum).to_string()]
+    );
+}
+
+#[test]
+fn auto_blocks() {
+    let output = run(&["--blocks-end", "6", "--native", "bfs", "--auto-blocks", "1"]);
+
+    // Each run records the trajectory it is part of, consisting of all runs.
+    let blocks = column(&output, "blocks");
+    let trajectory = &column(&output, "blocks_trajectory")[0];
+    let points: Vec<(String, f64)> = trajectory
+        .split(';')
+        .map(|point| {
+            let (blocks, value) = point.split_once(':').unwrap();
+            (blocks.to_string(), value.parse().unwrap())
+        })
+        .collect();
+
+    assert_eq!(
+        column(&output, "blocks_trajectory"),
+        vec![trajectory.clone(); blocks.len()]
+    );
+    assert_eq!(
+        points.iter().map(|(blocks, _)| blocks).collect::<Vec<_>>(),
+        blocks.iter().collect::<Vec<_>>()
+    );
+    assert_eq!(blocks[0], "2");
+
+    // The cut sizes are the values, the amount of blocks growing only while they decrease.
+    let cuts: Vec<f64> = column(&output, "cut_size")
+        .iter()
+        .map(|cut| cut.parse().unwrap())
+        .collect();
+
+    assert_eq!(points.iter().map(|&(_, cut)| cut).collect::<Vec<_>>(), cuts);
+    assert!(cuts.windows(2).rev().skip(1).all(|pair| pair[1] < pair[0]));
+    assert!(blocks.len() == 5 || cuts[cuts.len() - 1] >= cuts[cuts.len() - 2]);
+
+    let chosen = &column(&output, "blocks_chosen")[0];
+    let best = cuts.iter().copied().fold(f64::INFINITY, f64::min);
+    let index = blocks.iter().position(|blocks| blocks == chosen).unwrap();
+    assert_eq!(cuts[index], best);
+}
+
+#[test]
+fn preprocessing() {
+    for method in ["d4", "native", "none"] {
+        let output = run_bfs(&["--preprocessing", method]);
+
+        assert_eq!(column(&output, "preprocessing"), [method]);
+
+        // The count of the input is the one of the preprocessed CNF scaled by the factor.
+        let count: u128 = column(&output, "count_original")[0].parse().unwrap();
+        let factor: u128 = column(&output, "preprocessing_factor")[0].parse().unwrap();
+        assert_eq!((count * factor).to_string(), COUNT);
+    }
+
+    let output = run_bfs(&[
+        "--preprocessing",
+        "native",
+        "--preprocessing-disable",
+        "units,components",
+    ]);
+
+    assert_eq!(column(&output, "count_original"), [COUNT]);
+}
+
+#[test]
+fn components() {
+    // A second component over variables `11` and `12` as well as the free variable `13`.
+    let cnf = format!(
+        "{}11 12 0\n-11 -12 0\n",
+        CNF.replace("p cnf 10 12", "p cnf 13 14")
+    );
+
+    for arguments in [&[][..], &["--largest-component"][..]] {
+        let output = run_on(
+            &cnf,
+            &[&["-b", "2", "--native", "bfs"][..], arguments].concat(),
+        );
+
+        assert_eq!(column(&output, "count_original"), ["136"]);
+        assert_eq!(column(&output, "components"), ["2"]);
+        assert_eq!(column(&output, "component_sizes"), ["12;2"]);
+        assert_eq!(column(&output, "count_components"), ["136"]);
+    }
+}
+
+#[test]
+fn mismatching_components() {
+    // A d4 reporting the same count for every CNF.
+    let directory = TempDir::new().unwrap();
+    let d4 = script(&directory, "echo 's 3'");
+    let cnf = format!(
+        "{}11 12 0\n-11 -12 0\n",
+        CNF.replace("p cnf 10 12", "p cnf 13 14")
+    );
+
+    let arguments = [&BFS[..], &["--preprocessing", "none"]].concat();
+    let error = try_run_on(&cnf, d4.to_str().unwrap(), &arguments).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("components"), "{error}");
+}
+
+#[test]
+fn compilation_timeout() {
+    // A d4 counting the original and the conditioned CNF, but never finishing the parts.
+    let directory = TempDir::new().unwrap();
+    let d4 = script(
+        &directory,
+        r#"calls=$(cat "$0.calls" 2>/dev/null || echo 0)
+echo $((calls + 1)) > "$0.calls"
+[ "$calls" -lt 2 ] && echo "s 34" && exit 0
+sleep 10"#,
+    );
+
+    let arguments = [&BFS[..], &["--preprocessing", "none", "--timeout", "1"]].concat();
+    let output = try_run_on(CNF, d4.to_str().unwrap(), &arguments).unwrap();
+
+    // The times of both parts saturate instead of overflowing.
+    assert_eq!(column(&output, "status"), ["timeout"]);
+    assert_eq!(column(&output, "time_sum"), [u128::MAX.to_string()]);
+}
+
+#[test]
+fn unsatisfiable() {
+    let cnf = format!("{CNF}1 0\n-1 0\n").replace("p cnf 10 12", "p cnf 10 14");
+    let output = run_on(&cnf, &["-b", "2", "--native", "bfs"]);
+
+    assert_eq!(column(&output, "count_original"), ["0"]);
+    assert_eq!(column(&output, "status"), ["unsatisfiable"]);
+}
+
+#[test]
+fn failing_d4() {
+    // `false` neither preprocesses nor reports a count but exits with code `1`.
+    let error = try_run_on(CNF, "false", &["-b", "2", "--native", "bfs"]).unwrap_err();
+    assert!(error.to_string().contains("exited with code 1"), "{error}");
+
+    let error = try_run_on(
+        CNF,
+        "true",
+        &["-b", "2", "--native", "bfs", "--preprocessing", "none"],
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("no model count"), "{error}");
+}
+
+#[test]
+fn dump_ddnnf() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("ddnnf");
+
+    let output = run_bfs(&["--dump-ddnnf", prefix.to_str().unwrap()]);
+
+    // The fake d4 creates a disjunction of all models.
+    assert_eq!(column(&output, "nodes_original"), ["2"]);
+    assert_eq!(column(&output, "edges_original"), [COUNT]);
+    assert_eq!(column(&output, "depth_original"), ["1"]);
+    assert_eq!(column(&output, "decisions_original"), ["1"]);
+    assert_eq!(column(&output, "nodes_split"), ["2;2"]);
+
+    assert!(directory.path().join("ddnnf.nnf").is_file());
+    assert!(saved(&directory, "ddnnf", ".conditioned.nnf").is_file());
+    assert!(saved(&directory, "ddnnf", ".1.nnf").is_file());
+
+    // `--dump-file` is an alias.
+    let output = run_bfs(&["--dump-file", prefix.to_str().unwrap()]);
+    assert_eq!(column(&output, "nodes_original"), ["2"]);
+
+    // The dumped d-DNNFs agree with the counts reported.
+    let output = run_bfs(&["--dump-ddnnf", prefix.to_str().unwrap(), "--verify-ddnnf"]);
+    assert_eq!(column(&output, "count_original"), [COUNT]);
+}
+
+#[test]
+fn compose() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("ddnnf");
+
+    let output = run_bfs(&[
+        "--compose",
+        "8",
+        "--dump-ddnnf",
+        prefix.to_str().unwrap(),
+        "--verify-ddnnf",
+    ]);
+
+    assert_eq!(column(&output, "count_composed"), [COUNT]);
+    assert_ne!(column(&output, "assignments_composed"), ["0"]);
+    assert!(saved(&directory, "ddnnf", ".composed.nnf").is_file());
+
+    // Cuts larger than the maximum are not composed.
+    let output = run_bfs(&["--compose", "0"]);
+    assert_eq!(column(&output, "assignments_composed"), ["0"]);
+}
+
+#[test]
+fn recursive() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("trace");
+
+    let output = run_bfs(&[
+        "--recursive",
+        "--recursive-clauses",
+        "4",
+        "--save-trace",
+        prefix.to_str().unwrap(),
+    ]);
+
+    // The recursion conditions on further cuts, counting at most the conditioned models.
+    let count: u64 = column(&output, "count_recursive")[0].parse().unwrap();
+    let conditioned: u64 = column(&output, "count_conditioned")[0].parse().unwrap();
+    assert!(count > 0 && count <= conditioned);
+    assert_ne!(column(&output, "height_recursive"), ["0"]);
+
+    let trace = fs::read_to_string(saved(&directory, "trace", ".json")).unwrap();
+    assert!(trace.contains("\"children\""));
+    assert!(trace.contains(&format!("\"count\": \"{count}\"")));
+
+    // A single level equals the split without recursion.
+    let output = run_bfs(&["--recursive"]);
+    assert_eq!(column(&output, "height_recursive"), ["1"]);
+    assert_eq!(
+        column(&output, "count_recursive"),
+        column(&output, "count_conditioned")
+    );
+}
+
+#[test]
+fn dtree() {
+    let directory = TempDir::new().unwrap();
+    let prefix = directory.path().join("dtree");
+
+    let output = run_bfs(&["--dtree", "--save-dtree", prefix.to_str().unwrap()]);
+
+    let width: usize = column(&output, "width_dtree")[0].parse().unwrap();
+    let cutset: usize = column(&output, "max_cutset_dtree")[0].parse().unwrap();
+    assert!(cutset > 0 && width >= cutset);
+    assert_ne!(column(&output, "height_dtree"), ["0"]);
+
+    // A full binary tree with a leaf per clause.
+    let dtree = fs::read_to_string(saved(&directory, "dtree", ".dtree")).unwrap();
+    let lines: Vec<&str> = dtree.lines().collect();
+    assert_eq!(lines[0], "dt 23");
+    assert_eq!(
+        lines.iter().filter(|line| line.starts_with("L ")).count(),
+        12
+    );
+
+    // External partitioners bisect the induced hypergraphs written to files.
+    let output = run(&[
+        "-b",
+        "2",
+        "--kahypar-enable",
+        "--kahypar-path",
+        env!("CARGO_BIN_EXE_fake_kahypar"),
+        "--dtree",
+    ]);
+    assert_ne!(column(&output, "height_dtree"), ["0"]);
+}
+
+#[test]
+fn refine() {
+    let output = run_bfs(&["--refine"]);
+
+    let before: usize = column(&output, "cut_before_refinement")[0].parse().unwrap();
+    let after: usize = column(&output, "cut_after_refinement")[0].parse().unwrap();
+
+    assert!(after <= before);
+    assert_eq!(column(&output, "cut_size"), [after.to_string()]);
+    assert_eq!(column(&output, "count_original"), [COUNT]);
+}
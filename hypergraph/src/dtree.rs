@@ -0,0 +1,253 @@
+use crate::{Hypergraph, Partition};
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// A node of a dtree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    /// A leaf holding a vertex.
+    Leaf(usize),
+    /// An internal node holding the indices of its two children.
+    Internal(usize, usize),
+}
+
+/// A decomposition tree (dtree) of a hypergraph, being a full binary tree with its vertices as
+/// leaves.
+///
+/// The cutset of an internal node are the nets shared by both of its subtrees that are not in the
+/// cutset of any of its ancestors. The cluster of a node are its cutset and the nets below it in
+/// the cutsets of its ancestors, the nets of a leaf being its cluster.
+#[derive(Debug, Clone, Default)]
+pub struct Dtree {
+    /// The nodes, children preceding their parents and the root being the last one.
+    nodes: Vec<Node>,
+    /// The cutset of each node, empty for leaves.
+    cutsets: Vec<BTreeSet<usize>>,
+    /// The cluster of each node.
+    clusters: Vec<BTreeSet<usize>>,
+}
+
+impl Dtree {
+    /// The nodes, children preceding their parents and the root being the last one.
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// The index of the root, there being none for a hypergraph without vertices.
+    pub fn root(&self) -> Option<usize> {
+        self.nodes.len().checked_sub(1)
+    }
+
+    /// The cutset of a node.
+    pub fn cutset(&self, node: usize) -> &BTreeSet<usize> {
+        &self.cutsets[node]
+    }
+
+    /// The size of the cutset of each internal node.
+    pub fn cutset_sizes(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .filter(|&node| matches!(self.nodes[node], Node::Internal(..)))
+            .map(|node| self.cutsets[node].len())
+            .collect()
+    }
+
+    /// The size of the largest cutset.
+    pub fn max_cutset(&self) -> usize {
+        self.cutsets.iter().map(BTreeSet::len).max().unwrap_or(0)
+    }
+
+    /// The width, being the size of the largest cluster minus one.
+    pub fn width(&self) -> usize {
+        self.clusters
+            .iter()
+            .map(BTreeSet::len)
+            .max()
+            .unwrap_or(0)
+            .saturating_sub(1)
+    }
+
+    /// The amount of levels below the root.
+    pub fn height(&self) -> usize {
+        let mut heights = vec![0; self.nodes.len()];
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            if let &Node::Internal(left, right) = node {
+                heights[index] = heights[left].max(heights[right]) + 1;
+            }
+        }
+
+        heights.last().copied().unwrap_or(0)
+    }
+}
+
+/// Writes the dtree in the format of c2d, leaves referring to vertices and internal nodes to the
+/// lines of their children, both zero-based.
+impl Display for Dtree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "dt {}", self.nodes.len())?;
+
+        self.nodes.iter().try_for_each(|node| match node {
+            Node::Leaf(vertex) => writeln!(f, "L {vertex}"),
+            Node::Internal(left, right) => writeln!(f, "I {left} {right}"),
+        })
+    }
+}
+
+impl Hypergraph<usize> {
+    /// Builds a dtree by recursively bisecting the vertices.
+    ///
+    /// The bisection is given the vertices to split and returns a partition of them, its block `0`
+    /// going to the left subtree and all others to the right one. In case it leaves either side
+    /// empty, the vertices are split in halves by their order instead.
+    pub fn dtree<E>(
+        &self,
+        mut bisect: impl FnMut(&[usize]) -> Result<Partition, E>,
+    ) -> Result<Dtree, E> {
+        let vertices: Vec<usize> = self.vertices.keys().copied().collect();
+        let mut nodes = Vec::with_capacity(2 * vertices.len());
+
+        if !vertices.is_empty() {
+            build(&vertices, &mut bisect, &mut nodes)?;
+        }
+
+        Ok(self.annotate(nodes))
+    }
+
+    /// Calculates the cutsets and clusters of the nodes of a dtree.
+    fn annotate(&self, nodes: Vec<Node>) -> Dtree {
+        // The nets below each node.
+        let mut nets: Vec<BTreeSet<usize>> = Vec::with_capacity(nodes.len());
+
+        for node in &nodes {
+            let below = match *node {
+                Node::Leaf(vertex) => self.vertices[&vertex].nets.iter().copied().collect(),
+                Node::Internal(left, right) => nets[left].union(&nets[right]).copied().collect(),
+            };
+
+            nets.push(below);
+        }
+
+        // Going from the root down, keep track of the cutsets of the ancestors of each node.
+        let mut ancestors = vec![BTreeSet::new(); nodes.len()];
+        let mut cutsets = vec![BTreeSet::new(); nodes.len()];
+        let mut clusters = vec![BTreeSet::new(); nodes.len()];
+
+        for index in (0..nodes.len()).rev() {
+            let ancestor = std::mem::take(&mut ancestors[index]);
+
+            let Node::Internal(left, right) = nodes[index] else {
+                clusters[index] = std::mem::take(&mut nets[index]);
+                continue;
+            };
+
+            let cutset: BTreeSet<usize> = nets[left]
+                .intersection(&nets[right])
+                .filter(|&net| !ancestor.contains(net))
+                .copied()
+                .collect();
+
+            clusters[index] = nets[index]
+                .intersection(&ancestor)
+                .chain(&cutset)
+                .copied()
+                .collect();
+
+            let ancestor: BTreeSet<usize> = ancestor.union(&cutset).copied().collect();
+            ancestors[left] = ancestor.clone();
+            ancestors[right] = ancestor;
+            cutsets[index] = cutset;
+        }
+
+        Dtree {
+            nodes,
+            cutsets,
+            clusters,
+        }
+    }
+}
+
+/// Builds the subtree of the given vertices, returning the index of its root.
+fn build<E>(
+    vertices: &[usize],
+    bisect: &mut impl FnMut(&[usize]) -> Result<Partition, E>,
+    nodes: &mut Vec<Node>,
+) -> Result<usize, E> {
+    if let &[vertex] = vertices {
+        nodes.push(Node::Leaf(vertex));
+        return Ok(nodes.len() - 1);
+    }
+
+    let partition = bisect(vertices)?;
+
+    let (mut left, mut right): (Vec<usize>, Vec<usize>) =
+        (0..vertices.len()).partition(|&index| partition[index] == 0);
+
+    left.iter_mut().for_each(|index| *index = vertices[*index]);
+    right.iter_mut().for_each(|index| *index = vertices[*index]);
+
+    if left.is_empty() || right.is_empty() {
+        let (first, second) = vertices.split_at(vertices.len() / 2);
+        left = first.to_vec();
+        right = second.to_vec();
+    }
+
+    let left = build(&left, bisect, nodes)?;
+    let right = build(&right, bisect, nodes)?;
+
+    nodes.push(Node::Internal(left, right));
+    Ok(nodes.len() - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::dtree::Node;
+    use crate::{Hypergraph, Partition};
+    use std::convert::Infallible;
+
+    /// A path of four vertices, each net connecting two neighbors.
+    fn path() -> Hypergraph<usize> {
+        let mut graph = Hypergraph::new(3);
+
+        for net in 0..3 {
+            graph.add_pin(net, net);
+            graph.add_pin(net, net + 1);
+        }
+
+        graph
+    }
+
+    /// Bisects the vertices in halves by their order.
+    fn halves(vertices: &[usize]) -> Result<Partition, Infallible> {
+        Ok((0..vertices.len())
+            .map(|index| usize::from(2 * index >= vertices.len()))
+            .collect::<Vec<usize>>()
+            .into())
+    }
+
+    #[test]
+    fn dtree() {
+        let dtree = path().dtree(halves).unwrap();
+
+        assert_eq!(dtree.root(), Some(6));
+        assert_eq!(dtree.nodes()[6], Node::Internal(2, 5));
+        assert_eq!(dtree.cutset(6).iter().collect::<Vec<_>>(), [&1]);
+        assert_eq!(dtree.cutset_sizes(), [1, 1, 1]);
+        assert_eq!(dtree.width(), 1);
+        assert_eq!(dtree.height(), 2);
+        assert_eq!(
+            dtree.to_string(),
+            "dt 7\nL 0\nL 1\nI 0 1\nL 2\nL 3\nI 3 4\nI 2 5\n"
+        );
+    }
+
+    #[test]
+    fn degenerate_bisection() {
+        // Keeping all vertices on one side falls back to splitting them in halves.
+        let dtree = path()
+            .dtree(|vertices| Ok::<_, Infallible>(vec![0; vertices.len()].into()))
+            .unwrap();
+
+        assert_eq!(dtree.to_string(), path().dtree(halves).unwrap().to_string());
+        assert_eq!(Hypergraph::new(0).dtree(halves).unwrap().root(), None);
+    }
+}
@@ -10,7 +10,7 @@ mod hypergraph {
         type Hypergraph;
         fn new_hypergraph(num_vertices: usize, num_nets: usize) -> Box<Hypergraph>;
         fn add_pin(&mut self, net: usize, vertex: usize);
-        fn partition_random(&self, blocks: usize, imbalance: f32) -> Vec<usize>;
+        fn partition_random(&self, blocks: usize, imbalance: f32, seed: u64) -> Vec<usize>;
         fn partition_bfs(&self, blocks: usize) -> Vec<usize>;
         fn partition_dfs(&self, blocks: usize) -> Vec<usize>;
     }
@@ -1,5 +1,6 @@
 mod search;
 
+use bitvec::bitvec;
 use search::SearchIterator;
 use std::collections::{BTreeMap, VecDeque};
 use std::iter::Sum;
@@ -84,18 +85,62 @@ impl<W: Weight> Hypergraph<W> {
         self.vertices.values().map(|vertex| vertex.weight).sum()
     }
 
+    /// Finds the connected components, each given by its vertices in ascending order.
+    ///
+    /// Components are ordered by their smallest vertex.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut seen = bitvec![0; self.len()];
+        let mut components = Vec::new();
+
+        for &root in self.vertices.keys() {
+            if seen[root] {
+                continue;
+            }
+
+            seen.set(root, true);
+
+            // Collect all vertices reachable from the root, the component serving as the queue.
+            let mut component = vec![root];
+            let mut next = 0;
+
+            while let Some(&vertex) = component.get(next) {
+                next += 1;
+
+                for &neighbor in self.neighbors(vertex) {
+                    if !seen[neighbor] {
+                        seen.set(neighbor, true);
+                        component.push(neighbor);
+                    }
+                }
+            }
+
+            component.sort();
+            components.push(component);
+        }
+
+        components
+    }
+
     /// Iterates over the vertices via [breadth-first search](https://en.wikipedia.org/wiki/Breadth-first_search).
-    pub fn bfs(&self) -> SearchIterator<W, VecDeque<usize>> {
+    pub fn bfs(&self) -> SearchIterator<'_, W, VecDeque<usize>> {
         SearchIterator::new(self)
     }
 
     /// Iterates over the vertices via [depth-first search](https://en.wikipedia.org/wiki/Depth-first_search).
-    pub fn dfs(&self) -> SearchIterator<W, Vec<usize>> {
+    pub fn dfs(&self) -> SearchIterator<'_, W, Vec<usize>> {
         SearchIterator::new(self)
     }
 }
 
 impl Hypergraph<usize> {
+    /// Adds a vertex with the given weight, keeping its nets in case it already exists.
+    pub fn add_vertex(&mut self, vertex: usize, weight: usize) {
+        self.vertices
+            .entry(vertex)
+            .or_insert_with(|| Vertex::new(weight))
+            .weight = weight;
+    }
+
     pub fn add_pin(&mut self, net: usize, vertex: usize) {
         self.vertices
             .entry(vertex)
@@ -111,11 +156,43 @@ impl Hypergraph<usize> {
 impl From<&Graph> for Hypergraph<usize> {
     fn from(value: &Graph) -> Self {
         let mut graph = Self::new(value.nets.len());
+        let offset = usize::from(value.header.one_indexed);
+
+        // Add all vertices first to also keep the ones not contained in any net.
+        (0..value.header.num_vertices).for_each(|vertex| {
+            let weight = if value.header.format.contains_vertex_weights() {
+                value.vertex_weights[vertex] as usize
+            } else {
+                1
+            };
+
+            graph.add_vertex(vertex, weight);
+        });
 
         value
             .pins()
-            .for_each(|(net_index, vertex)| graph.add_pin(net_index, vertex));
+            .for_each(|(vertex, net)| graph.add_pin(net, vertex - offset));
 
         graph
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Hypergraph;
+
+    #[test]
+    fn components() {
+        let mut graph = Hypergraph::new(3);
+
+        graph.add_pin(0, 0);
+        graph.add_pin(0, 3);
+        graph.add_pin(1, 3);
+        graph.add_pin(1, 5);
+        graph.add_pin(2, 1);
+        graph.add_pin(2, 4);
+        graph.add_vertex(2, 1);
+
+        assert_eq!(graph.components(), vec![vec![0, 3, 5], vec![1, 4], vec![2]]);
+    }
+}
@@ -1,6 +1,8 @@
 //mod ffi;
+pub mod dtree;
 mod hypergraph;
 pub mod partitioner;
 
+pub use dtree::Dtree;
 pub use hypergraph::Hypergraph;
-pub use partitioner::Partition;
+pub use partitioner::{Partition, Separator};
@@ -2,8 +2,10 @@ mod bfs;
 mod dfs;
 mod partition;
 mod random;
+mod separator;
 
 pub use partition::Partition;
+pub use separator::Separator;
 
 /// Keeps track of a partition of a hypergraph.
 struct PartitionManager {
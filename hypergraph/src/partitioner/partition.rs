@@ -18,6 +18,36 @@ impl Partition {
     pub fn iter(&self) -> impl Iterator<Item = &usize> {
         self.0.iter()
     }
+
+    /// The block of each vertex.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.0
+    }
+
+    /// Calculates the size of each block, considering each vertex to have a unit weight.
+    ///
+    /// Panics in case a vertex is assigned to a block outside the given amount of blocks, so
+    /// partitions read from partitioners need to be checked beforehand.
+    pub fn block_sizes(&self, blocks: usize) -> Vec<usize> {
+        let mut sizes = vec![0; blocks];
+        self.iter().for_each(|&block| sizes[block] += 1);
+        sizes
+    }
+
+    /// Calculates the imbalance of this partition, considering each vertex to have a unit weight.
+    ///
+    /// It is the relative amount the heaviest block exceeds the ideal block size of
+    /// `⌈vertices / blocks⌉`, the same as the epsilon parameter of partitioners bounds.
+    pub fn imbalance(&self, blocks: usize) -> f64 {
+        let ideal = self.0.len().div_ceil(blocks);
+
+        if ideal == 0 {
+            return 0.0;
+        }
+
+        let heaviest = self.block_sizes(blocks).into_iter().max().unwrap_or(0);
+        heaviest as f64 / ideal as f64 - 1.0
+    }
 }
 
 impl Default for Partition {
@@ -71,3 +101,19 @@ impl Display for Partition {
         self.iter().try_for_each(|block| writeln!(f, "{block}"))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Partition;
+
+    #[test]
+    fn imbalance() {
+        let balanced = Partition::from(vec![0, 1, 0, 1]);
+        assert_eq!(balanced.block_sizes(2), vec![2, 2]);
+        assert_eq!(balanced.imbalance(2), 0.0);
+
+        let imbalanced = Partition::from(vec![0, 0, 0, 1]);
+        assert_eq!(imbalanced.block_sizes(2), vec![3, 1]);
+        assert_eq!(imbalanced.imbalance(2), 0.5);
+    }
+}
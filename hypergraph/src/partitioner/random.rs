@@ -1,12 +1,17 @@
 use super::{Partition, PartitionManager};
 use crate::Hypergraph;
 use rand::distr::{Distribution, Uniform};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
 
 impl Hypergraph<usize> {
-    pub fn partition_random(&self, blocks: usize, imbalance: f32) -> Partition {
+    /// Partitions the hypergraph by randomly assigning vertices to blocks.
+    ///
+    /// The same seed always results in the same partition.
+    pub fn partition_random(&self, blocks: usize, imbalance: f32, seed: u64) -> Partition {
         // RNG for sampling the vertices.
-        let mut rng = rand::rng();
+        let mut rng = StdRng::seed_from_u64(seed);
 
         // Sampler for choosing a block a vertex goes into.
         let range = Uniform::new(0, blocks).expect("Invalid block range.");
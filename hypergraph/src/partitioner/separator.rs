@@ -0,0 +1,243 @@
+use super::Partition;
+use crate::Hypergraph;
+
+/// A vertex separator of the clique expansion of a hypergraph.
+///
+/// Removing the vertices of the separator leaves no net containing vertices of different blocks.
+#[derive(Debug)]
+pub struct Separator {
+    /// The block of each vertex, `None` for the vertices of the separator.
+    pub blocks: Vec<Option<usize>>,
+}
+
+impl Separator {
+    /// The vertices of the separator in ascending order.
+    pub fn vertices(&self) -> Vec<usize> {
+        (0..self.blocks.len())
+            .filter(|&vertex| self.blocks[vertex].is_none())
+            .collect()
+    }
+}
+
+impl Hypergraph<usize> {
+    /// Finds a vertex separator splitting the vertices into blocks of similar weight.
+    ///
+    /// The heaviest block is bisected until there are enough blocks. A bisection orders the
+    /// vertices of a block by breadth-first search and moves the second half by weight to the new
+    /// block, separating the smaller boundary between both halves.
+    ///
+    /// Currently, no imbalance parameter is supported.
+    pub fn separator(&self, blocks: usize) -> Separator {
+        let mut labels = vec![Some(0); self.len()];
+
+        for new in 1..blocks {
+            // Bisect the heaviest block.
+            let mut weights = vec![0; new];
+
+            (0..self.len()).for_each(|vertex| {
+                if let Some(block) = labels[vertex] {
+                    weights[block] += self.vertices[&vertex].weight;
+                }
+            });
+
+            let heaviest = (0..new)
+                .max_by_key(|&block| (weights[block], std::cmp::Reverse(block)))
+                .expect("There is at least one block.");
+
+            self.bisect(&mut labels, heaviest, new);
+        }
+
+        Separator { blocks: labels }
+    }
+
+    /// Partitions the nets along a vertex separator, each net going to the block of its vertices
+    /// not in the separator.
+    ///
+    /// Nets only containing vertices of the separator go to the block with the fewest nets.
+    /// Entry `i` of the partition is the block of net `i`, not of vertex `i`.
+    pub fn partition_separator(&self, blocks: usize) -> Partition {
+        let separator = self.separator(blocks);
+
+        let mut partition = vec![None; self.nets.len()];
+        let mut sizes = vec![0; blocks];
+
+        for (net, vertices) in self.nets.iter().enumerate() {
+            if let Some(block) = vertices.iter().find_map(|&vertex| separator.blocks[vertex]) {
+                partition[net] = Some(block);
+                sizes[block] += 1;
+            }
+        }
+
+        partition
+            .into_iter()
+            .map(|block| {
+                block.unwrap_or_else(|| {
+                    let smallest = (0..blocks)
+                        .min_by_key(|&block| sizes[block])
+                        .expect("There is at least one block.");
+
+                    sizes[smallest] += 1;
+                    smallest
+                })
+            })
+            .collect::<Vec<usize>>()
+            .into()
+    }
+
+    /// Splits a block in two, separating both by setting the labels of the separator to `None`.
+    fn bisect(&self, labels: &mut [Option<usize>], block: usize, new: usize) {
+        let order = self.order_within(labels, block);
+
+        let total: usize = order
+            .iter()
+            .map(|vertex| self.vertices[vertex].weight)
+            .sum();
+
+        // Keep the first half by weight in the block and move the rest to the new one.
+        let mut kept = 0;
+
+        for &vertex in &order {
+            if 2 * kept < total {
+                kept += self.vertices[&vertex].weight;
+            } else {
+                labels[vertex] = Some(new);
+            }
+        }
+
+        // The vertices of each half adjacent to the other one.
+        let boundary = |labels: &[Option<usize>], own: usize, other: usize| -> Vec<usize> {
+            order
+                .iter()
+                .copied()
+                .filter(|&vertex| labels[vertex] == Some(own))
+                .filter(|&vertex| {
+                    self.neighbors(vertex)
+                        .any(|&neighbor| labels[neighbor] == Some(other))
+                })
+                .collect()
+        };
+
+        let kept = boundary(labels, block, new);
+        let moved = boundary(labels, new, block);
+        let separator = if kept.len() < moved.len() {
+            kept
+        } else {
+            moved
+        };
+
+        separator.iter().for_each(|&vertex| labels[vertex] = None);
+
+        // Vertices only adjacent to one half are not needed for separating them.
+        for vertex in separator {
+            let touches = |block| {
+                self.neighbors(vertex)
+                    .any(|&neighbor| labels[neighbor] == Some(block))
+            };
+
+            if !touches(new) {
+                labels[vertex] = Some(block);
+            } else if !touches(block) {
+                labels[vertex] = Some(new);
+            }
+        }
+    }
+
+    /// Orders the vertices of a block by breadth-first search, ignoring all others.
+    ///
+    /// Each connected part of the block is searched starting at a vertex far from the others.
+    fn order_within(&self, labels: &[Option<usize>], block: usize) -> Vec<usize> {
+        let mut seen = vec![false; self.len()];
+        let mut order = Vec::new();
+
+        for root in 0..self.len() {
+            if seen[root] || labels[root] != Some(block) {
+                continue;
+            }
+
+            // Search once to find the last vertex reached, starting from it again.
+            let search = self.search_within(labels, block, root);
+            let start = *search.last().expect("The root is reached.");
+
+            for vertex in self.search_within(labels, block, start) {
+                seen[vertex] = true;
+                order.push(vertex);
+            }
+        }
+
+        order
+    }
+
+    /// Searches breadth-first from a vertex, only following vertices of the same block.
+    fn search_within(&self, labels: &[Option<usize>], block: usize, root: usize) -> Vec<usize> {
+        let mut seen = vec![false; self.len()];
+        seen[root] = true;
+
+        // The order serves as the queue.
+        let mut order = vec![root];
+        let mut next = 0;
+
+        while let Some(&vertex) = order.get(next) {
+            next += 1;
+
+            for &neighbor in self.neighbors(vertex) {
+                if !seen[neighbor] && labels[neighbor] == Some(block) {
+                    seen[neighbor] = true;
+                    order.push(neighbor);
+                }
+            }
+        }
+
+        order
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Hypergraph;
+
+    /// A path of five vertices, each net connecting two neighbors.
+    fn path() -> Hypergraph<usize> {
+        let mut graph = Hypergraph::new(4);
+
+        for net in 0..4 {
+            graph.add_pin(net, net);
+            graph.add_pin(net, net + 1);
+        }
+
+        graph
+    }
+
+    #[test]
+    fn separator() {
+        let graph = path();
+        let separator = graph.separator(2);
+
+        // A single inner vertex separates the path.
+        assert_eq!(separator.vertices().len(), 1);
+        assert!((1..4).contains(&separator.vertices()[0]));
+
+        // No net connects both blocks.
+        for vertices in &graph.nets {
+            let blocks: Vec<usize> = vertices
+                .iter()
+                .filter_map(|&vertex| separator.blocks[vertex])
+                .collect();
+
+            assert!(blocks.windows(2).all(|pair| pair[0] == pair[1]));
+        }
+
+        // Both blocks are used.
+        assert!(separator.blocks.contains(&Some(0)));
+        assert!(separator.blocks.contains(&Some(1)));
+    }
+
+    #[test]
+    fn partition_separator() {
+        let partition = path().partition_separator(2);
+
+        // Each net is assigned, neither block being empty.
+        assert_eq!(partition.as_slice().len(), 4);
+        assert_eq!(partition.block_sizes(2).iter().sum::<usize>(), 4);
+        assert!(partition.block_sizes(2).iter().all(|&size| size > 0));
+    }
+}
@@ -1,261 +1,150 @@
-use crate::{Format, Graph, Header, Net, Weight};
-use clap::ValueEnum;
-use dimacs::{Clause, Instance, Lit, Sign, Var};
-use std::cmp::PartialEq;
-use strum::Display;
-
-/// A single clause of a CNF.
-struct CnfClause(Vec<i64>);
-
-impl CnfClause {
-    /// Iterates of all literals of this clause.
-    pub fn literals(&self) -> impl Iterator<Item = &i64> {
-        self.0.iter()
-    }
-
-    /// Iterates over all variables of this clause.
-    pub fn variables(&self) -> impl Iterator<Item = u64> + '_ {
-        self.literals().map(|literal| literal.unsigned_abs())
-    }
-
-    /// Returns the amount of literals in this clause.
-    pub fn len(&self) -> usize {
-        self.0.len()
-    }
-
-    /// Checks whether this clause contains the positive literal of the given variable.
-    pub fn contains_positive(&self, var: u64) -> bool {
-        self.0.contains(&(var as i64))
-    }
-
-    /// Checks whether this clause contains the negative literal of the given variable.
-    pub fn contains_negative(&self, var: u64) -> bool {
-        self.0.contains(&-(var as i64))
-    }
-
-    /// Checks whether this clause contains the given variable.
-    pub fn contains(&self, var: u64) -> bool {
-        self.contains_positive(var) || self.contains_negative(var)
-    }
-
-    /// Calculates how many times the given variable is found in this clause. Can be `0`, `1` or `2`.
-    pub fn occurrence(&self, var: u64) -> usize {
-        let positive = self.contains_positive(var);
-        let negative = self.contains_negative(var);
-
-        if positive && negative {
-            return 2;
-        }
-
-        if positive || negative {
-            return 1;
-        }
-
-        0
-    }
-}
-
-#[derive(Debug, Copy, Clone, Default, PartialEq, ValueEnum, Display)]
-pub enum VariableHeuristic {
-    #[default]
-    None,
-    MAXO,
-    MOMS,
-    MAMS,
-}
-
-/// An alternative CNF representation for calculating heuristics.
-struct Cnf(Vec<CnfClause>);
-
-impl Cnf {
-    /// Iterates over all clauses of this CNF.
-    pub fn iter(&self) -> impl Iterator<Item = &CnfClause> {
-        self.0.iter()
-    }
-
-    /// Iterates over all clauses of minimum size.
-    fn minimum_size_clauses(&self) -> impl Iterator<Item = &CnfClause> {
-        let minimum_size = self.0.iter().map(CnfClause::len).min().unwrap();
-        self.iter()
-            .filter(move |clause| clause.len() == minimum_size)
-    }
-
-    pub fn maxo(&self, var: u64) -> usize {
-        self.iter().map(|clause| clause.occurrence(var)).sum()
-    }
-
-    pub fn moms(&self, var: u64) -> usize {
-        self.minimum_size_clauses()
-            .map(|clause| clause.occurrence(var))
-            .sum()
-    }
-
-    pub fn mams(&self, var: u64) -> usize {
-        self.maxo(var) + self.moms(var)
+//! An owned CNF representation with the operations needed for splitting CNFs along partitions.
+
+mod clause;
+mod error;
+mod formula;
+mod heuristic;
+mod literal;
+mod occurrences;
+mod split;
+
+pub use clause::Clause;
+pub use error::CnfError;
+pub use formula::Cnf;
+pub use heuristic::VariableHeuristic;
+pub use literal::Literal;
+pub use occurrences::Occurrences;
+pub use split::{cut_variables, Split};
+
+#[cfg(test)]
+mod test {
+    use super::{cut_variables, Cnf, CnfError, Literal};
+    use std::str::FromStr;
+
+    fn cnf() -> Cnf {
+        Cnf::from_str("c a comment\np cnf 5 4\n1 2 0\n-2 3 0\n3 4 0\n-1 -4 0\n").unwrap()
+    }
+
+    fn literals(values: &[i64]) -> Vec<Literal> {
+        values
+            .iter()
+            .map(|&value| Literal::new(value).unwrap())
+            .collect()
     }
 
-    pub fn jeroslaw_wang(&self, var: u64) -> f64 {
-        self.iter()
-            .filter(|clause| clause.contains(var))
-            .map(|clause| 2f64.powi(-(clause.len() as i32)))
-            .sum()
+    #[test]
+    fn parse_and_serialize() {
+        assert_eq!(
+            cnf().to_string(),
+            "p cnf 5 4\n1 2 0\n-2 3 0\n3 4 0\n-1 -4 0\n"
+        );
+        assert_eq!(Cnf::from_str(&cnf().to_string()).unwrap(), cnf());
     }
-}
 
-impl From<&Clause> for CnfClause {
-    fn from(value: &Clause) -> Self {
-        CnfClause(
-            value
-                .lits()
-                .iter()
-                .map(|lit| match lit.sign() {
-                    Sign::Pos => lit.var().to_u64() as i64,
-                    Sign::Neg => -(lit.var().to_u64() as i64),
-                })
-                .collect(),
+    #[test]
+    fn names() {
+        let named = Cnf::from_str(
+            "c 1 Root\nc 3 Feature With Spaces\nc 9 Unknown\nc no variable\np cnf 5 4\n1 2 0\n-2 3 0\n3 4 0\n-1 -4 0\n",
         )
-    }
-}
-
-impl From<&Instance> for Cnf {
-    fn from(value: &Instance) -> Self {
-        match value {
-            Instance::Cnf { clauses, .. } => Cnf(clauses.iter().map(CnfClause::from).collect()),
-            Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-        }
-    }
-}
-
-impl From<(&Instance, VariableHeuristic)> for Graph {
-    fn from((instance, heuristic): (&Instance, VariableHeuristic)) -> Self {
-        let cnf = Cnf::from(instance);
-
-        // Transform the CNF into a hypergraph ...
-        let nets: Vec<Net> = cnf
-            .iter()
-            // by transforming every clause into a net.
-            .map(|clause| {
-                clause
-                    .variables()
-                    .map(|variable| variable as usize)
-                    .collect()
-            })
-            .map(Net)
-            .collect();
-
-        let num_nets = nets.len();
-        let num_vertices = match instance {
-            Instance::Cnf { num_vars, .. } => *num_vars as usize,
-            Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-        };
-
-        if heuristic == VariableHeuristic::None {
-            return Graph {
-                header: Header {
-                    num_nets,
-                    num_vertices,
-                    format: Format::Unweighted,
-                    one_indexed: true,
-                },
-                vertex_weights: Vec::new(),
-                net_weights: Vec::new(),
-                nets,
-            };
-        }
-
-        // Add the vertex weights as calculated by the chosen heuristic.
-        let mut weights = Vec::with_capacity(num_vertices);
-
-        // CNF variables are `1`-index which has to be taken into account for calculating the weights.
-        (1..=num_vertices).for_each(|vertex| {
-            // Calculate the weight using the chosen heuristic.
-            let weight = match heuristic {
-                VariableHeuristic::None => unreachable!(),
-                VariableHeuristic::MAXO => cnf.maxo(vertex as u64),
-                VariableHeuristic::MOMS => cnf.moms(vertex as u64),
-                VariableHeuristic::MAMS => cnf.mams(vertex as u64),
-            };
-
-            weights.push(weight);
-        });
-
-        // Find the maximum weight.
-        let max_weight = weights
-            .iter()
-            .max()
-            .expect("There should be at least one vertex weight.");
-
-        // Scale all weights relative to the maximum one.
-        let vertex_weights = weights
-            .iter()
-            // Calculate the ratio of any given weight to the maximum one.
-            /*            .map(|&weight| weight as f64 / *max_weight as f64)
-            // Scale the weight using this ratio relative to the maximum possible weight.
-            .map(|ratio| {
-                let weight = (Weight::MAX as f64 * ratio) as Weight;
-                weight
-            })*/
-            .map(|&weight| {
-                let weight_adapted = Weight::MAX - weight as Weight;
-                weight_adapted
+        .unwrap();
+
+        assert_eq!(named.name(1), Some("Root"));
+        assert_eq!(named.name(3), Some("Feature With Spaces"));
+        assert_eq!(named.names().len(), 2);
+        assert_eq!(Cnf::from_str(&named.to_string()).unwrap(), named);
+
+        // Names follow the variables through conditioning, splitting and compacting.
+        let conditioned = named.condition(&literals(&[-2])).unwrap();
+        let (compacted, _) = conditioned.split(&[1, 1, 0, 1, 1], 2).unwrap().parts[0].compact();
+
+        assert_eq!(compacted.name(1), Some("Feature With Spaces"));
+        assert_eq!(compacted.name(2), None);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            Cnf::from_str("p cnf 2 1\n1 3 0\n").unwrap_err(),
+            CnfError::Variable {
+                variable: 3,
+                num_vars: 2
+            }
+        );
+        assert!(matches!(
+            Cnf::from_str("p sat 2\n(1)").unwrap_err(),
+            CnfError::NotCnf | CnfError::Parse(_)
+        ));
+        assert_eq!(Literal::new(0).unwrap_err(), CnfError::Literal);
+    }
+
+    #[test]
+    fn occurrences() {
+        let occurrences = cnf().occurrences();
+
+        assert_eq!(occurrences.literal(Literal::new(3).unwrap()), [1, 2]);
+        assert_eq!(occurrences.literal(Literal::new(-4).unwrap()), [3]);
+        assert_eq!(occurrences.count(Literal::new(5).unwrap()), 0);
+        assert_eq!(occurrences.variable(1).copied().collect::<Vec<_>>(), [0, 3]);
+    }
+
+    #[test]
+    fn condition_and_compact() {
+        let conditioned = cnf().condition(&literals(&[-2])).unwrap();
+        assert_eq!(conditioned.len(), 5);
+
+        let (compacted, variables) =
+            conditioned.split(&[0, 1, 1, 1, 1], 2).unwrap().parts[1].compact();
+
+        assert_eq!(variables, [1, 2, 3, 4]);
+        assert_eq!(compacted.num_vars(), 4);
+        assert_eq!(compacted.clauses()[3].to_string(), "-2 0");
+    }
+
+    #[test]
+    fn split_by_block() {
+        let split = cnf().split(&[2, 0, 2, 2], 3).unwrap();
+
+        assert_eq!(
+            split.parts.iter().map(Cnf::len).collect::<Vec<_>>(),
+            [1, 0, 3]
+        );
+        assert_eq!(split.empty_blocks, [1]);
+        assert_eq!(
+            cut_variables(&split.parts).into_iter().collect::<Vec<_>>(),
+            [2, 3]
+        );
+    }
+
+    #[test]
+    fn check_partition() {
+        assert_eq!(cnf().check_partition(&[2, 0, 2, 2], 3), Ok(()));
+        assert_eq!(
+            cnf().check_partition(&[0, 1, 0, 5], 2),
+            Err(CnfError::Block {
+                clause: 3,
+                block: 5,
+                blocks: 2
             })
-            .map(|weight| if weight == 0 { 1 } else { weight })
-            .collect();
-
-        Graph {
-            header: Header {
-                num_nets,
-                num_vertices,
-                format: Format::VertexWeights,
-                one_indexed: true,
-            },
-            vertex_weights,
-            net_weights: Vec::new(),
-            nets,
-        }
-    }
-}
-
-impl From<&Instance> for Graph {
-    /// Converts a CNF into an unweighted hypergraph.
-    ///
-    /// Only works for a CNF instance, will panic otherwise.
-    fn from(value: &Instance) -> Self {
-        let (clauses, num_vars) = match value {
-            Instance::Cnf { clauses, num_vars } => (clauses, num_vars),
-            Instance::Sat { .. } => panic!("Expected CNF but found SAT DIMACS."),
-        };
-
-        // Transform the CNF into a hypergraph ...
-        let nets = clauses
-            .iter()
-            // by transforming every clause into a net.
-            .map(|clause| {
-                clause
-                    .lits()
-                    .iter()
-                    .cloned()
-                    .map(Lit::var)
-                    .map(Var::to_u64)
-                    .map(|vertex| vertex as usize)
-                    .collect()
-            })
-            .map(Net)
-            .collect();
-
-        let num_nets = clauses.len();
-        let num_vertices = *num_vars as usize;
-
-        Graph {
-            header: Header {
-                num_nets,
-                num_vertices,
-                format: Format::Unweighted,
-                one_indexed: true,
-            },
-            vertex_weights: Vec::new(),
-            net_weights: Vec::new(),
-            nets,
-        }
+        );
+    }
+
+    #[test]
+    fn split_invalid() {
+        assert_eq!(
+            cnf().split(&[0, 1, 0], 2).unwrap_err(),
+            CnfError::PartitionLength {
+                clauses: 4,
+                partition: 3
+            }
+        );
+
+        assert_eq!(
+            cnf().split(&[0, 1, 2, 0], 2).unwrap_err(),
+            CnfError::Block {
+                clause: 2,
+                block: 2,
+                blocks: 2
+            }
+        );
     }
 }
@@ -0,0 +1,70 @@
+use super::Literal;
+use std::fmt::{Display, Formatter};
+
+/// A disjunction of literals.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Clause(Vec<Literal>);
+
+impl Clause {
+    /// Creates a clause from its literals.
+    pub fn new(literals: Vec<Literal>) -> Self {
+        Self(literals)
+    }
+
+    /// Iterates over all literals of this clause.
+    pub fn literals(&self) -> impl Iterator<Item = &Literal> {
+        self.0.iter()
+    }
+
+    /// Iterates over all variables of this clause.
+    pub fn variables(&self) -> impl Iterator<Item = u64> + '_ {
+        self.literals().map(Literal::variable)
+    }
+
+    /// Returns the amount of literals in this clause.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Checks whether this clause contains no literals and is therefore unsatisfiable.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks whether this clause contains the given literal.
+    pub fn contains(&self, literal: Literal) -> bool {
+        self.0.contains(&literal)
+    }
+
+    /// Checks whether this clause contains the given variable.
+    pub fn contains_variable(&self, variable: u64) -> bool {
+        self.variables().any(|other| other == variable)
+    }
+
+    /// Calculates how many times the given variable is found in this clause. Can be `0`, `1` or `2`.
+    pub fn occurrence(&self, variable: u64) -> usize {
+        usize::from(self.contains(Literal::from_variable(variable, true)))
+            + usize::from(self.contains(Literal::from_variable(variable, false)))
+    }
+}
+
+impl From<Vec<Literal>> for Clause {
+    fn from(value: Vec<Literal>) -> Self {
+        Self(value)
+    }
+}
+
+impl FromIterator<Literal> for Clause {
+    fn from_iter<T: IntoIterator<Item = Literal>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Display for Clause {
+    /// Writes the clause in the DIMACS format, terminated by `0`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.literals()
+            .try_for_each(|literal| write!(f, "{literal} "))?;
+        write!(f, "0")
+    }
+}
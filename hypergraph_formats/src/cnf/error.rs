@@ -0,0 +1,51 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Reasons for a CNF or an operation on it being invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CnfError {
+    /// The input is not valid DIMACS.
+    Parse(String),
+    /// The DIMACS input describes a SAT instance instead of a CNF.
+    NotCnf,
+    /// A literal is `0`, which is reserved for terminating clauses.
+    Literal,
+    /// A variable exceeds the amount of variables of the CNF.
+    Variable { variable: u64, num_vars: u64 },
+    /// The partition does not assign exactly one block to each clause.
+    PartitionLength { clauses: usize, partition: usize },
+    /// A clause was assigned to a block outside the given amount of blocks.
+    Block {
+        clause: usize,
+        block: usize,
+        blocks: usize,
+    },
+}
+
+impl Display for CnfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CnfError::Parse(error) => write!(f, "Failed to parse DIMACS: {error}"),
+            CnfError::NotCnf => write!(f, "Expected CNF but found SAT DIMACS."),
+            CnfError::Literal => write!(f, "Literals must not be 0."),
+            CnfError::Variable { variable, num_vars } => write!(
+                f,
+                "Variable {variable} exceeds the {num_vars} variables of the CNF."
+            ),
+            CnfError::PartitionLength { clauses, partition } => write!(
+                f,
+                "The partition contains {partition} entries for {clauses} clauses."
+            ),
+            CnfError::Block {
+                clause,
+                block,
+                blocks,
+            } => write!(
+                f,
+                "Clause {clause} is assigned to block {block} of only {blocks} blocks."
+            ),
+        }
+    }
+}
+
+impl Error for CnfError {}
@@ -0,0 +1,260 @@
+use super::{Clause, CnfError, Literal, Occurrences, Split};
+use dimacs::{parse_dimacs, Instance};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// A formula in conjunctive normal form over the variables `1..=num_vars`.
+///
+/// Variables may be named, as done by `c <variable> <name>` comments of CNFs created from feature
+/// models.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cnf {
+    num_vars: u64,
+    clauses: Vec<Clause>,
+    names: BTreeMap<u64, String>,
+}
+
+impl Cnf {
+    /// Creates a CNF, checking that all variables are within `1..=num_vars`.
+    pub fn new(num_vars: u64, clauses: Vec<Clause>) -> Result<Self, CnfError> {
+        if let Some(variable) = clauses
+            .iter()
+            .flat_map(Clause::variables)
+            .find(|&variable| variable > num_vars)
+        {
+            return Err(CnfError::Variable { variable, num_vars });
+        }
+
+        Ok(Self {
+            num_vars,
+            clauses,
+            names: BTreeMap::new(),
+        })
+    }
+
+    /// Names the variables, dropping names of variables not within `1..=num_vars`.
+    pub fn with_names(mut self, mut names: BTreeMap<u64, String>) -> Self {
+        names.retain(|&variable, _| (1..=self.num_vars).contains(&variable));
+        self.names = names;
+        self
+    }
+
+    /// The names of all named variables.
+    pub fn names(&self) -> &BTreeMap<u64, String> {
+        &self.names
+    }
+
+    /// The name of a variable, if any.
+    pub fn name(&self, variable: u64) -> Option<&str> {
+        self.names.get(&variable).map(String::as_str)
+    }
+
+    /// The amount of variables, including those not occurring in any clause.
+    pub fn num_vars(&self) -> u64 {
+        self.num_vars
+    }
+
+    /// All clauses of this CNF.
+    pub fn clauses(&self) -> &[Clause] {
+        &self.clauses
+    }
+
+    /// The amount of clauses.
+    pub fn len(&self) -> usize {
+        self.clauses.len()
+    }
+
+    /// Checks whether this CNF contains no clauses.
+    pub fn is_empty(&self) -> bool {
+        self.clauses.is_empty()
+    }
+
+    /// The set of variables occurring in any clause.
+    pub fn variables(&self) -> BTreeSet<u64> {
+        self.clauses.iter().flat_map(Clause::variables).collect()
+    }
+
+    /// The set of literals occurring in any clause.
+    pub fn literals(&self) -> BTreeSet<Literal> {
+        self.clauses
+            .iter()
+            .flat_map(Clause::literals)
+            .copied()
+            .collect()
+    }
+
+    /// Creates the occurrence lists of all literals.
+    pub fn occurrences(&self) -> Occurrences {
+        Occurrences::new(self)
+    }
+
+    /// Conditions this CNF on the given assignment by adding each literal as a unit clause.
+    pub fn condition(&self, assignment: &[Literal]) -> Result<Cnf, CnfError> {
+        let mut clauses = self.clauses.clone();
+
+        clauses.extend(assignment.iter().map(|&literal| Clause::new(vec![literal])));
+
+        Ok(Cnf::new(self.num_vars, clauses)?.with_names(self.names.clone()))
+    }
+
+    /// Renumbers the variables densely, dropping those not occurring in any clause.
+    ///
+    /// Names are kept for the renumbered variables.
+    ///
+    /// Returns the compacted CNF and the variable map, containing the original variable of each
+    /// new variable at its index minus one.
+    pub fn compact(&self) -> (Cnf, Vec<u64>) {
+        // The variables are kept in their original order.
+        let variables: Vec<u64> = self.variables().into_iter().collect();
+
+        let renumbering: HashMap<u64, u64> = variables
+            .iter()
+            .enumerate()
+            .map(|(index, &variable)| (variable, index as u64 + 1))
+            .collect();
+
+        let clauses = self
+            .clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .literals()
+                    .map(|literal| {
+                        Literal::from_variable(
+                            renumbering[&literal.variable()],
+                            literal.is_positive(),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let names = self
+            .names
+            .iter()
+            .filter_map(|(variable, name)| Some((*renumbering.get(variable)?, name.clone())))
+            .collect();
+
+        let compacted = Cnf {
+            num_vars: variables.len() as u64,
+            clauses,
+            names,
+        };
+
+        (compacted, variables)
+    }
+
+    /// Checks that a partition assigns a block below the given amount of blocks to each clause,
+    /// where entry `i` of the partition is the block of clause `i`.
+    pub fn check_partition(&self, partition: &[usize], blocks: usize) -> Result<(), CnfError> {
+        if self.clauses.len() != partition.len() {
+            return Err(CnfError::PartitionLength {
+                clauses: self.clauses.len(),
+                partition: partition.len(),
+            });
+        }
+
+        match partition.iter().position(|&block| block >= blocks) {
+            Some(clause) => Err(CnfError::Block {
+                clause,
+                block: partition[clause],
+                blocks,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Splits this CNF into one part per block, where entry `i` of the partition is the block of
+    /// clause `i`.
+    ///
+    /// Each part keeps the variables of this CNF, their names and the relative order of its clauses.
+    pub fn split(&self, partition: &[usize], blocks: usize) -> Result<Split, CnfError> {
+        self.check_partition(partition, blocks)?;
+
+        let mut parts: Vec<Vec<Clause>> = vec![Vec::new(); blocks];
+
+        // Add each clause to the part of its block.
+        for (&block, clause) in partition.iter().zip(&self.clauses) {
+            parts[block].push(clause.clone());
+        }
+
+        let empty_blocks = parts
+            .iter()
+            .enumerate()
+            .filter(|(_, clauses)| clauses.is_empty())
+            .map(|(block, _)| block)
+            .collect();
+
+        Ok(Split {
+            parts: parts
+                .into_iter()
+                .map(|clauses| Cnf {
+                    num_vars: self.num_vars,
+                    clauses,
+                    names: self.names.clone(),
+                })
+                .collect(),
+            empty_blocks,
+        })
+    }
+}
+
+impl TryFrom<&Instance> for Cnf {
+    type Error = CnfError;
+
+    fn try_from(value: &Instance) -> Result<Self, Self::Error> {
+        match value {
+            Instance::Cnf { clauses, num_vars } => Cnf::new(
+                *num_vars,
+                clauses
+                    .iter()
+                    .map(|clause| clause.lits().iter().map(Literal::from).collect())
+                    .collect(),
+            ),
+            Instance::Sat { .. } => Err(CnfError::NotCnf),
+        }
+    }
+}
+
+impl FromStr for Cnf {
+    type Err = CnfError;
+
+    /// Parses a CNF in the DIMACS format, keeping the names of `c <variable> <name>` comments and
+    /// dropping all other comments.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let instance = parse_dimacs(s).map_err(|error| CnfError::Parse(format!("{error:?}")))?;
+        Ok(Cnf::try_from(&instance)?.with_names(parse_names(s)))
+    }
+}
+
+impl Display for Cnf {
+    /// Writes the CNF in the DIMACS format, preceded by the names of the variables as comments.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.names
+            .iter()
+            .try_for_each(|(variable, name)| writeln!(f, "c {variable} {name}"))?;
+
+        writeln!(f, "p cnf {} {}", self.num_vars, self.clauses.len())?;
+        self.clauses
+            .iter()
+            .try_for_each(|clause| writeln!(f, "{clause}"))
+    }
+}
+
+/// Collects the variable names given by `c <variable> <name>` comments of a CNF in the DIMACS
+/// format.
+fn parse_names(s: &str) -> BTreeMap<u64, String> {
+    s.lines()
+        .filter_map(|line| {
+            let comment = line.trim().strip_prefix("c ")?;
+            let (variable, name) = comment.trim_start().split_once(char::is_whitespace)?;
+            let name = name.trim();
+
+            match variable.parse() {
+                Ok(variable) if !name.is_empty() => Some((variable, name.to_string())),
+                _ => None,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,126 @@
+use super::{Clause, Cnf};
+use crate::{Format, Graph, Header, Net, Weight};
+use clap::ValueEnum;
+use strum::Display;
+
+/// Heuristics for weighing the variables of a CNF.
+#[derive(Debug, Copy, Clone, Default, PartialEq, ValueEnum, Display)]
+pub enum VariableHeuristic {
+    #[default]
+    None,
+    MAXO,
+    MOMS,
+    MAMS,
+}
+
+impl Cnf {
+    /// Iterates over all clauses of minimum size.
+    fn minimum_size_clauses(&self) -> impl Iterator<Item = &Clause> {
+        let minimum_size = self.clauses().iter().map(Clause::len).min().unwrap_or(0);
+        self.clauses()
+            .iter()
+            .filter(move |clause| clause.len() == minimum_size)
+    }
+
+    /// Counts the occurrences of a variable in all clauses.
+    pub fn maxo(&self, var: u64) -> usize {
+        self.clauses()
+            .iter()
+            .map(|clause| clause.occurrence(var))
+            .sum()
+    }
+
+    /// Counts the occurrences of a variable in the clauses of minimum size.
+    pub fn moms(&self, var: u64) -> usize {
+        self.minimum_size_clauses()
+            .map(|clause| clause.occurrence(var))
+            .sum()
+    }
+
+    /// Sums up [`Cnf::maxo`] and [`Cnf::moms`].
+    pub fn mams(&self, var: u64) -> usize {
+        self.maxo(var) + self.moms(var)
+    }
+
+    /// Weighs each clause containing the variable exponentially smaller with its size.
+    pub fn jeroslaw_wang(&self, var: u64) -> f64 {
+        self.clauses()
+            .iter()
+            .filter(|clause| clause.contains_variable(var))
+            .map(|clause| 2f64.powi(-(clause.len() as i32)))
+            .sum()
+    }
+}
+
+impl From<(&Cnf, VariableHeuristic)> for Graph {
+    fn from((cnf, heuristic): (&Cnf, VariableHeuristic)) -> Self {
+        let graph = Graph::from(cnf);
+
+        if heuristic == VariableHeuristic::None {
+            return graph;
+        }
+
+        // Add the vertex weights as calculated by the chosen heuristic.
+        let mut weights = Vec::with_capacity(graph.header.num_vertices);
+
+        // CNF variables are `1`-index which has to be taken into account for calculating the weights.
+        (1..=graph.header.num_vertices).for_each(|vertex| {
+            // Calculate the weight using the chosen heuristic.
+            let weight = match heuristic {
+                VariableHeuristic::None => unreachable!(),
+                VariableHeuristic::MAXO => cnf.maxo(vertex as u64),
+                VariableHeuristic::MOMS => cnf.moms(vertex as u64),
+                VariableHeuristic::MAMS => cnf.mams(vertex as u64),
+            };
+
+            weights.push(weight);
+        });
+
+        // Invert all weights relative to the maximum possible one.
+        let vertex_weights = weights
+            .iter()
+            .map(|&weight| Weight::MAX - weight as Weight)
+            .map(|weight| if weight == 0 { 1 } else { weight })
+            .collect();
+
+        Graph {
+            header: Header {
+                format: Format::VertexWeights,
+                ..graph.header
+            },
+            vertex_weights,
+            ..graph
+        }
+    }
+}
+
+impl From<&Cnf> for Graph {
+    /// Converts a CNF into an unweighted hypergraph.
+    fn from(value: &Cnf) -> Self {
+        // Transform the CNF into a hypergraph ...
+        let nets = value
+            .clauses()
+            .iter()
+            // by transforming every clause into a net.
+            .map(|clause| {
+                clause
+                    .variables()
+                    .map(|variable| variable as usize)
+                    .collect()
+            })
+            .map(Net)
+            .collect();
+
+        Graph {
+            header: Header {
+                num_nets: value.len(),
+                num_vertices: value.num_vars() as usize,
+                format: Format::Unweighted,
+                one_indexed: true,
+            },
+            vertex_weights: Vec::new(),
+            net_weights: Vec::new(),
+            nets,
+        }
+    }
+}
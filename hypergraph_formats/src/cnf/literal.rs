@@ -0,0 +1,65 @@
+use super::CnfError;
+use dimacs::{Lit, Sign};
+use std::fmt::{Display, Formatter};
+use std::ops::Not;
+
+/// A variable or its negation, represented as in DIMACS by a non-zero signed integer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Literal(i64);
+
+impl Literal {
+    /// Creates a literal from its DIMACS representation, which must not be `0`.
+    pub fn new(value: i64) -> Result<Self, CnfError> {
+        if value == 0 {
+            return Err(CnfError::Literal);
+        }
+
+        Ok(Self(value))
+    }
+
+    /// Creates the positive or negative literal of a variable.
+    pub fn from_variable(variable: u64, positive: bool) -> Self {
+        assert_ne!(variable, 0, "Variables start at 1.");
+
+        if positive {
+            Self(variable as i64)
+        } else {
+            Self(-(variable as i64))
+        }
+    }
+
+    /// The variable of this literal.
+    pub fn variable(&self) -> u64 {
+        self.0.unsigned_abs()
+    }
+
+    /// Whether this literal is the variable itself instead of its negation.
+    pub fn is_positive(&self) -> bool {
+        self.0 > 0
+    }
+
+    /// The DIMACS representation of this literal.
+    pub fn value(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Not for Literal {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl From<&Lit> for Literal {
+    fn from(value: &Lit) -> Self {
+        Self::from_variable(value.var().to_u64(), value.sign() == Sign::Pos)
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,68 @@
+use super::{Cnf, Literal};
+
+/// The clauses each literal of a CNF occurs in, referenced by their index.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Occurrences {
+    /// Indexed by the variable minus one.
+    positive: Vec<Vec<usize>>,
+    /// Indexed by the variable minus one.
+    negative: Vec<Vec<usize>>,
+}
+
+impl Occurrences {
+    /// Creates the occurrence lists of all literals in a CNF.
+    pub fn new(cnf: &Cnf) -> Self {
+        let num_vars = cnf.num_vars() as usize;
+
+        let mut occurrences = Self {
+            positive: vec![Vec::new(); num_vars],
+            negative: vec![Vec::new(); num_vars],
+        };
+
+        for (index, clause) in cnf.clauses().iter().enumerate() {
+            for &literal in clause.literals() {
+                let clauses = occurrences.list_mut(literal);
+
+                // A literal repeated within a clause is only listed once.
+                if clauses.last() != Some(&index) {
+                    clauses.push(index);
+                }
+            }
+        }
+
+        occurrences
+    }
+
+    /// The clauses the given literal occurs in.
+    pub fn literal(&self, literal: Literal) -> &[usize] {
+        let index = literal.variable() as usize - 1;
+
+        if literal.is_positive() {
+            &self.positive[index]
+        } else {
+            &self.negative[index]
+        }
+    }
+
+    /// The clauses the given variable occurs in, positively or negatively.
+    pub fn variable(&self, variable: u64) -> impl Iterator<Item = &usize> {
+        self.literal(Literal::from_variable(variable, true))
+            .iter()
+            .chain(self.literal(Literal::from_variable(variable, false)))
+    }
+
+    /// How many clauses the given literal occurs in.
+    pub fn count(&self, literal: Literal) -> usize {
+        self.literal(literal).len()
+    }
+
+    fn list_mut(&mut self, literal: Literal) -> &mut Vec<usize> {
+        let index = literal.variable() as usize - 1;
+
+        if literal.is_positive() {
+            &mut self.positive[index]
+        } else {
+            &mut self.negative[index]
+        }
+    }
+}
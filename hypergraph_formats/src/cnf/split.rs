@@ -0,0 +1,29 @@
+use super::Cnf;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A CNF split into parts along a partition of its clauses.
+#[derive(Debug, Clone)]
+pub struct Split {
+    /// The part of each block, indexed by the block. Parts of empty blocks contain no clauses.
+    pub parts: Vec<Cnf>,
+    /// The blocks no clause was assigned to.
+    pub empty_blocks: Vec<usize>,
+}
+
+/// Calculates the cut between multiple CNFs.
+///
+/// The cut consists of those variables that occur in more than one of them.
+pub fn cut_variables(cnfs: &[Cnf]) -> BTreeSet<u64> {
+    let mut occurrences: BTreeMap<u64, usize> = BTreeMap::new();
+
+    // Count the CNFs each variable occurs in.
+    cnfs.iter()
+        .flat_map(Cnf::variables)
+        .for_each(|variable| *occurrences.entry(variable).or_default() += 1);
+
+    occurrences
+        .into_iter()
+        .filter(|&(_, count)| count > 1)
+        .map(|(variable, _)| variable)
+        .collect()
+}
@@ -128,6 +128,62 @@ impl Graph {
         }
     }
 
+    /// Creates the subhypergraph induced by the given vertices, each given by its zero-based index.
+    ///
+    /// Vertex `vertices[i]` becomes vertex `i` (respecting the indexing). Nets are restricted to the
+    /// given vertices, dropping those left with fewer than two of them. Weights are kept.
+    pub fn induced(&self, vertices: &[usize]) -> Self {
+        let offset = usize::from(self.header.one_indexed);
+
+        // The new index of each vertex contained.
+        let mut indices = vec![None; self.header.num_vertices];
+
+        vertices
+            .iter()
+            .enumerate()
+            .for_each(|(index, &vertex)| indices[vertex] = Some(index + offset));
+
+        let mut nets = Vec::new();
+        let mut net_weights = Vec::new();
+
+        self.nets.iter().enumerate().for_each(|(index, net)| {
+            let net: Vec<VertexIndex> = net
+                .iter()
+                .filter_map(|&vertex| indices[vertex - offset])
+                .collect();
+
+            if net.len() < 2 {
+                return;
+            }
+
+            nets.push(Net(net));
+
+            if self.header.format.contains_net_weights() {
+                net_weights.push(self.net_weights[index]);
+            }
+        });
+
+        let vertex_weights = if self.header.format.contains_vertex_weights() {
+            vertices
+                .iter()
+                .map(|&vertex| self.vertex_weights[vertex])
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Self {
+            header: Header {
+                num_nets: nets.len(),
+                num_vertices: vertices.len(),
+                ..self.header
+            },
+            nets,
+            vertex_weights,
+            net_weights,
+        }
+    }
+
     /// Iterates over all pins (vertex <-> net pair) of the hypergraph.
     pub fn pins(&self) -> impl Iterator<Item = Pin> + '_ {
         // Consider each net ...
@@ -205,4 +261,22 @@ mod test {
         assert_eq!(primal.dual(), dual);
         assert_eq!(primal.dual().dual(), primal);
     }
+
+    #[test]
+    fn induced() {
+        let induced = Graph {
+            header: Header {
+                num_nets: 2,
+                num_vertices: 3,
+                format: Format::Unweighted,
+                one_indexed: false,
+            },
+            nets: vec![Net(vec![1, 0]), Net(vec![2, 0])],
+            vertex_weights: vec![],
+            net_weights: vec![],
+        };
+
+        // The first net keeps only vertex `0`, dropping it.
+        assert_eq!(graph().induced(&[3, 2, 0]), induced);
+    }
 }
@@ -25,6 +25,7 @@ impl<T: Parsable> ParsableHMETIS for T {
 }
 
 /// Enables a structure to be parsed from the hMETIS hypergraph format.
+#[allow(clippy::result_unit_err)]
 pub trait FromStringHMETIS {
     /// Parses this structure from the hMETIS hypergraph format.
     fn from_string_hmetis(input: &str) -> Result<Self, ()>
@@ -1,6 +1,6 @@
 use super::comment::Space;
 use super::{ParsableHMETIS, SerializeHMETIS};
-use crate::{Graph, Header, Net};
+use crate::{Graph, Header, Net, Weight};
 use nom::character::complete::space1;
 use nom::multi::{many0, separated_list1};
 use nom::sequence::tuple;
@@ -27,14 +27,33 @@ impl ParsableHMETIS for Graph {
             Space::parse_hmetis,
         ))(input)?;
 
-        let nets = nets.into_iter().map(Net).collect();
+        let mut nets = nets;
+
+        // The lines following the nets contain the vertex weights.
+        let vertex_weights = if header.format.contains_vertex_weights() {
+            nets.split_off(header.num_nets.min(nets.len()))
+                .into_iter()
+                .flatten()
+                .map(|weight| weight as Weight)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // The first number of each net is its weight.
+        let net_weights = if header.format.contains_net_weights() {
+            nets.iter_mut().map(|net| net.remove(0) as Weight).collect()
+        } else {
+            Vec::new()
+        };
+
         Ok((
             input,
             Graph {
                 header,
-                nets,
-                vertex_weights: Vec::new(),
-                net_weights: Vec::new(),
+                nets: nets.into_iter().map(Net).collect(),
+                vertex_weights,
+                net_weights,
             },
         ))
     }
@@ -125,6 +144,29 @@ mod test {
         );
     }
 
+    #[test]
+    fn parse_weighted() {
+        let input = r#"4 7 11
+2 1 2
+1 1 7 5 6
+3 5 6 4
+1 2 3 4
+1
+5
+2
+2
+2
+1
+3
+"#;
+
+        let mut expected = graph_vertex_weights();
+        expected.header.format = Format::Weighted;
+        expected.net_weights = vec![2, 1, 3, 1];
+
+        assert_eq!(Graph::from_string_hmetis(input).unwrap(), expected);
+    }
+
     #[test]
     fn serialize_unweighted() {
         let expected = r#"4 7
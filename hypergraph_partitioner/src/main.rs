@@ -30,6 +30,10 @@ struct Args {
     /// Block-imbalance tolerance
     #[arg(short, long, env)]
     epsilon: Option<f32>,
+
+    /// Seed for randomized partitioning algorithms
+    #[arg(short, long, env, default_value_t = 0)]
+    seed: u64,
 }
 
 pub fn main() {
@@ -53,6 +57,7 @@ pub fn main() {
             args.blocks,
             args.epsilon
                 .expect("Random partitioning requires the `epsilon` imbalance parameter."),
+            args.seed,
         ),
     };
 
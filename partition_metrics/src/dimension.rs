@@ -1,35 +1,19 @@
-use crate::utils::{extract_clauses, extract_literals, extract_variables};
-use dimacs::Instance;
+use hypergraph_formats::cnf::Cnf;
 
-pub fn num_clauses(original: &Instance, split: &[Instance]) -> (usize, Vec<usize>) {
-    (
-        extract_clauses(original).len(),
-        split
-            .iter()
-            .map(extract_clauses)
-            .map(|clauses| clauses.len())
-            .collect(),
-    )
+pub fn num_clauses(original: &Cnf, split: &[Cnf]) -> (usize, Vec<usize>) {
+    (original.len(), split.iter().map(Cnf::len).collect())
 }
 
-pub fn num_variables(original: &Instance, split: &[Instance]) -> (usize, Vec<usize>) {
+pub fn num_variables(original: &Cnf, split: &[Cnf]) -> (usize, Vec<usize>) {
     (
-        extract_variables(original).count(),
-        split
-            .iter()
-            .map(extract_variables)
-            .map(Iterator::count)
-            .collect(),
+        original.variables().len(),
+        split.iter().map(|cnf| cnf.variables().len()).collect(),
     )
 }
 
-pub fn num_literals(original: &Instance, split: &[Instance]) -> (usize, Vec<usize>) {
+pub fn num_literals(original: &Cnf, split: &[Cnf]) -> (usize, Vec<usize>) {
     (
-        extract_literals(original).count(),
-        split
-            .iter()
-            .map(extract_literals)
-            .map(Iterator::count)
-            .collect(),
+        original.literals().len(),
+        split.iter().map(|cnf| cnf.literals().len()).collect(),
     )
 }
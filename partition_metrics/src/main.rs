@@ -1,16 +1,12 @@
-mod cnf;
 mod csv;
 mod dimension;
-mod utils;
 mod width;
 
-use crate::cnf::serialize_cnf;
 use clap::Parser;
-use cnf::{get_cut_variables, split_cnf};
 use csv::to_csv;
-use dimacs::parse_dimacs;
 use dimension::{num_clauses, num_literals, num_variables};
 use hypergraph::partitioner::Partition;
+use hypergraph_formats::cnf::{cut_variables, Cnf};
 use std::fs;
 use std::io::{self, Error, ErrorKind};
 use std::path::PathBuf;
@@ -26,8 +22,13 @@ struct Args {
     #[arg(short, long, env)]
     partition: PathBuf,
 
+    /// How many blocks the partition has.
+    /// Defaults to one more than the highest block in the partition.
+    #[arg(short, long, env)]
+    blocks: Option<usize>,
+
     /// Where to write split CNFs to.
-    /// Will get the block numbers appended.
+    /// Will get the block numbers appended, including those of empty blocks.
     #[arg(short, long, env)]
     output_cnfs: PathBuf,
 }
@@ -41,18 +42,24 @@ fn main() -> io::Result<()> {
     // and the partition.
     let partition = Partition::from(args.partition);
 
-    // Parse the CNF instance.
-    let original = match parse_dimacs(&cnf) {
-        Ok(instance) => instance,
-        Err(error) => {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Failed to parse input DIMACS: {:?}", error),
-            ));
-        }
-    };
+    // Parse the CNF.
+    let original: Cnf = cnf
+        .parse()
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+
+    let blocks = args
+        .blocks
+        .unwrap_or_else(|| partition.iter().max().map_or(0, |&block| block + 1));
+
+    let split = original
+        .split(partition.as_slice(), blocks)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, error))?;
+
+    if !split.empty_blocks.is_empty() {
+        eprintln!("Empty blocks: {:?}", split.empty_blocks);
+    }
 
-    let split = split_cnf(&partition, &original);
+    let split = split.parts;
 
     let output_path = args.output_cnfs.into_os_string();
 
@@ -61,10 +68,10 @@ fn main() -> io::Result<()> {
         path.push(".");
         path.push(i.to_string());
         path.push(".cnf");
-        fs::write(path, serialize_cnf(instance)).expect("Failed to save split CNF.");
+        fs::write(path, instance.to_string()).expect("Failed to save split CNF.");
     }
 
-    let cut = get_cut_variables(&split);
+    let cut = cut_variables(&split);
 
     println!("cut_size,num_clauses_original,num_clauses_split,num_variables_original,num_variables_split,num_literals_original,num_literals_split,width_original,width_split,density_original,density_split");
     println!(
@@ -1,15 +1,14 @@
 use crate::dimension::{num_clauses, num_variables};
-use crate::utils::extract_clauses;
-use dimacs::{Clause, Instance};
+use hypergraph_formats::cnf::{Clause, Cnf};
 
-pub fn clause_width(original: &Instance, split: &[Instance]) -> (usize, Vec<usize>) {
+pub fn clause_width(original: &Cnf, split: &[Cnf]) -> (usize, Vec<usize>) {
     (
-        width(extract_clauses(original)),
-        split.iter().map(extract_clauses).map(width).collect(),
+        width(original.clauses()),
+        split.iter().map(Cnf::clauses).map(width).collect(),
     )
 }
 
-pub fn clause_density(original: &Instance, split: &[Instance]) -> (usize, Vec<usize>) {
+pub fn clause_density(original: &Cnf, split: &[Cnf]) -> (usize, Vec<usize>) {
     let (clauses_original, clauses_split) = num_clauses(original, split);
     let (variables_original, variables_split) = num_variables(original, split);
 
@@ -20,15 +19,13 @@ pub fn clause_density(original: &Instance, split: &[Instance]) -> (usize, Vec<us
         clauses_split
             .iter()
             .zip(variables_split)
-            .map(|(clauses, variables)| clauses / variables)
+            // Empty parts have a density of `0`.
+            .map(|(clauses, variables)| clauses.checked_div(variables).unwrap_or(0))
             .collect(),
     )
 }
 
+/// The size of the largest clause, `0` if there are none.
 fn width(clauses: &[Clause]) -> usize {
-    clauses
-        .iter()
-        .map(Clause::len)
-        .max()
-        .expect("There should be at least one clause.")
+    clauses.iter().map(Clause::len).max().unwrap_or(0)
 }